@@ -11,6 +11,15 @@ public enum TranscribeError: Error, LocalizedError, Equatable {
     case invalidApiKey(String)
     case jsonError(String)
     case otherError(String)
+    case cancelled(String)
+    case bufferTooSmall(String)
+    case timeout(String)
+    case notFound(String)
+    case rateLimited(String)
+    case unauthorized(String)
+    case streamClosed(String)
+    case panic(String)
+    case runtimeShutDown(String)
     
     /// Initialize from C ErrorCode and optional message
     public init?(code: ErrorCode, message: String?) {
@@ -35,6 +44,26 @@ public enum TranscribeError: Error, LocalizedError, Equatable {
             self = .jsonError(errorMessage)
         case .otherError:
             self = .otherError(errorMessage)
+        case .cancelled:
+            self = .cancelled(errorMessage)
+        case .bufferTooSmall:
+            self = .bufferTooSmall(errorMessage)
+        case .timeout:
+            self = .timeout(errorMessage)
+        case .notFound:
+            self = .notFound(errorMessage)
+        case .rateLimited:
+            self = .rateLimited(errorMessage)
+        case .unauthorized:
+            self = .unauthorized(errorMessage)
+        case .streamClosed:
+            self = .streamClosed(errorMessage)
+        case .panic:
+            self = .panic(errorMessage)
+        case .runtimeShutDown:
+            self = .runtimeShutDown(errorMessage)
+        case .unknownError:
+            self = .otherError(errorMessage)
         @unknown default:
             return nil
         }
@@ -60,6 +89,24 @@ public enum TranscribeError: Error, LocalizedError, Equatable {
             return "JSON Error: \(msg)"
         case .otherError(let msg):
             return "Other Error: \(msg)"
+        case .cancelled(let msg):
+            return "Cancelled: \(msg)"
+        case .bufferTooSmall(let msg):
+            return "Buffer Too Small: \(msg)"
+        case .timeout(let msg):
+            return "Timeout: \(msg)"
+        case .notFound(let msg):
+            return "Not Found: \(msg)"
+        case .rateLimited(let msg):
+            return "Rate Limited: \(msg)"
+        case .unauthorized(let msg):
+            return "Unauthorized: \(msg)"
+        case .streamClosed(let msg):
+            return "Stream Closed: \(msg)"
+        case .panic(let msg):
+            return "Internal Panic: \(msg)"
+        case .runtimeShutDown(let msg):
+            return "Runtime Shut Down: \(msg)"
         }
     }
     
@@ -83,6 +130,24 @@ public enum TranscribeError: Error, LocalizedError, Equatable {
             return "A JSON parsing error occurred. The response format may be incorrect."
         case .otherError:
             return "An unexpected error occurred. Please try again or contact support."
+        case .cancelled:
+            return "The operation was cancelled before it completed."
+        case .bufferTooSmall:
+            return "The output buffer is too small for the result."
+        case .timeout:
+            return "The request did not complete in time."
+        case .notFound:
+            return "The requested resource does not exist."
+        case .rateLimited:
+            return "Too many requests were sent to the server."
+        case .unauthorized:
+            return "The request is not authorized."
+        case .streamClosed:
+            return "The WebSocket message stream has ended."
+        case .panic:
+            return "The native library hit an internal error."
+        case .runtimeShutDown:
+            return "The native runtime has been shut down."
         }
     }
     
@@ -106,6 +171,24 @@ public enum TranscribeError: Error, LocalizedError, Equatable {
             return "Check the response format and ensure the API version matches your client."
         case .otherError:
             return "Review the error message for details and try again. If the issue persists, contact support."
+        case .cancelled:
+            return "Start the operation again if it is still needed."
+        case .bufferTooSmall:
+            return "Retry with a larger buffer."
+        case .timeout:
+            return "Check your network connection and try again, or increase the timeout."
+        case .notFound:
+            return "Verify the task ID or resource identifier."
+        case .rateLimited:
+            return "Wait a moment before sending more requests."
+        case .unauthorized:
+            return "Check that your token has access to this resource."
+        case .streamClosed:
+            return "Create a new session to continue receiving results."
+        case .panic:
+            return "Report the error message to support; the library may need to be restarted."
+        case .runtimeShutDown:
+            return "Initialize the runtime again before making more calls."
         }
     }
 }
@@ -124,5 +207,15 @@ public enum ErrorCode: Int32 {
     case invalidApiKey = 7
     case jsonError = 8
     case otherError = 9
+    case cancelled = 10
+    case bufferTooSmall = 11
+    case timeout = 12
+    case notFound = 13
+    case rateLimited = 14
+    case unauthorized = 15
+    case streamClosed = 16
+    case panic = 17
+    case runtimeShutDown = 18
+    case unknownError = -1
 }
 
@@ -289,6 +289,11 @@ extension DianyaAPI {
                                     self.messageSubject.send(message)
                                 }
                             }
+                        } catch TranscribeError.streamClosed(_) {
+                            await MainActor.run {
+                                self.messageSubject.send(completion: .finished)
+                            }
+                            break
                         } catch {
                             await MainActor.run {
                                 if let error = error as? TranscribeError {
@@ -342,6 +347,9 @@ extension DianyaAPI {
                                 // 超时或没有消息
                                 timeoutCount += 1
                             }
+                        } catch TranscribeError.streamClosed(_) {
+                            Self.logger.info("Message stream closed")
+                            break
                         } catch {
                             Self.logger.error("Error receiving message: \(String(describing: error), privacy: .public)")
                             if let error = error as? TranscribeError {
@@ -361,6 +369,7 @@ extension DianyaAPI {
         /// Receive a single message asynchronously
         /// - Parameter timeout: Timeout in seconds (0 = immediate return)
         /// - Returns: Received message JSON string, or nil if timeout
+        /// - Throws: `TranscribeError.streamClosed` once the message stream has ended
         public func receive(timeout: TimeInterval = 0) async throws -> String? {
             let timeoutMs = UInt64(timeout * 1000)
             return try await receiveMessage(timeout: timeoutMs)
@@ -383,25 +392,35 @@ extension DianyaAPI {
                         throw TranscribeError.otherError("WebSocket not connected")
                     }
                 
-                let bufferSize = 64 * 1024 // 64KB buffer
-                var buffer = [CChar](repeating: 0, count: bufferSize)
-                var messageLen: UInt = UInt(bufferSize)
-                let errorManager = FfiErrorManager()
-                
-                let code: Int32 = withUnsafeMutablePointer(to: &messageLen) { messageLenPtr in
-                    buffer.withUnsafeMutableBufferPointer { bufferPtr in
-                        transcribe_ffi_ws_receive(
-                            handle,
-                            bufferPtr.baseAddress,
-                            messageLenPtr,
-                            timeout,
-                            errorManager.getPointer()
-                        )
+                var bufferSize = 64 * 1024 // 64KB buffer
+                var buffer: [CChar]
+                var messageLen: UInt
+                while true {
+                    buffer = [CChar](repeating: 0, count: bufferSize)
+                    messageLen = UInt(bufferSize)
+                    let errorManager = FfiErrorManager()
+                    
+                    let code: Int32 = withUnsafeMutablePointer(to: &messageLen) { messageLenPtr in
+                        buffer.withUnsafeMutableBufferPointer { bufferPtr in
+                            transcribe_ffi_ws_receive(
+                                handle,
+                                bufferPtr.baseAddress,
+                                messageLenPtr,
+                                timeout,
+                                errorManager.getPointer()
+                            )
+                        }
                     }
+                    
+                    // The message stays in the handle; read it again with the required size
+                    if code == ErrorCode.bufferTooSmall.rawValue && Int(messageLen) > bufferSize {
+                        bufferSize = Int(messageLen)
+                        continue
+                    }
+                    try FFIBridge.callFFI(errorCode: code, errorManager: errorManager)
+                    break
                 }
                 
-                try FFIBridge.callFFI(errorCode: code, errorManager: errorManager)
-                
                 if messageLen == 0 {
                     return (nil as String?)
                 }
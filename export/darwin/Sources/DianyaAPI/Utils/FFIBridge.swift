@@ -59,8 +59,10 @@ internal class FFIBridge {
 // MARK: - C Structure Definitions (matching C header)
 
 internal struct FfiError {
-    var code: ErrorCode
+    /// Raw C error code; map with `ErrorCode(rawValue:)`, since newer libraries may add codes
+    var code: Int32
     var message: UnsafeMutablePointer<CChar>?
+    var details: OpaquePointer?
 }
 
 internal struct FfiShareLink {
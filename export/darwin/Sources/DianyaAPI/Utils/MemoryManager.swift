@@ -51,7 +51,7 @@ internal class FfiErrorManager {
     private var error: FfiError
     
     init() {
-        self.error = FfiError(code: .otherError, message: nil)
+        self.error = FfiError(code: 0, message: nil, details: nil)
     }
     
     func getPointer() -> UnsafeMutablePointer<FfiError> {
@@ -59,7 +59,7 @@ internal class FfiErrorManager {
     }
     
     func toSwiftError() -> TranscribeError? {
-        let code = ErrorCode(rawValue: error.code.rawValue) ?? .otherError
+        let code = ErrorCode(rawValue: error.code) ?? .otherError
         let message = error.message.map { String(cString: $0) }
         return TranscribeError(code: code, message: message)
     }
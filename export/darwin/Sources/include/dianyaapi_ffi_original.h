@@ -5,6 +5,26 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * 翻译任务类型（转写 / 总结）
+ */
+typedef enum FfiTranslateTaskType {
+  Transcribe,
+  Summary,
+} FfiTranslateTaskType;
+
+/**
+ * 翻译语言
+ */
+typedef enum FfiLanguage {
+  ChineseSimplified,
+  EnglishUS,
+  Japanese,
+  Korean,
+  French,
+  German,
+} FfiLanguage;
+
 typedef enum ErrorCode {
   WsError = 1,
   HttpError = 2,
@@ -15,8 +35,67 @@ typedef enum ErrorCode {
   InvalidApiKey = 7,
   JsonError = 8,
   OtherError = 9,
+  Cancelled = 10,
+  /**
+   * 输出缓冲区不足，所需大小见错误详情中的 `required_size`
+   */
+  BufferTooSmall = 11,
+  Timeout = 12,
+  NotFound = 13,
+  RateLimited = 14,
+  Unauthorized = 15,
+  /**
+   * WebSocket 消息流已结束
+   */
+  StreamClosed = 16,
+  /**
+   * 本库内部发生 panic（包括因此被毒化的锁）
+   */
+  Panic = 17,
+  /**
+   * runtime 已通过 `transcribe_ffi_runtime_shutdown` 关闭
+   */
+  RuntimeShutDown = 18,
+  UnknownError = -1,
 } ErrorCode;
 
+/**
+ * 日志级别
+ */
+typedef enum FfiLogLevel {
+  /**
+   * 关闭日志
+   */
+  Off = 0,
+  Error = 1,
+  Warn = 2,
+  Info = 3,
+  Debug = 4,
+  Trace = 5,
+} FfiLogLevel;
+
+/**
+ * 可取消操作的状态
+ */
+typedef enum FfiOperationState {
+  /**
+   * 执行中
+   */
+  Pending,
+  /**
+   * 已成功完成
+   */
+  Completed,
+  /**
+   * 已失败
+   */
+  Failed,
+  /**
+   * 已取消
+   */
+  Cancelled,
+} FfiOperationState;
+
 /**
  * 状态中任务类型
  */
@@ -28,55 +107,64 @@ typedef enum FfiTranscribeTaskType {
 } FfiTranscribeTaskType;
 
 /**
- * 翻译语言
+ * 任务事件类型
  */
-typedef enum FfiLanguage {
-  ChineseSimplified,
-  EnglishUS,
-  Japanese,
-  Korean,
-  French,
-  German,
-} FfiLanguage;
+typedef enum FfiWatchEventKind {
+  /**
+   * 任务状态变化（尚未结束）
+   */
+  StatusChanged,
+  /**
+   * 任务已完成，之后不再监视
+   */
+  Completed,
+  /**
+   * 任务失败（失败状态、不可重试的查询错误、状态无法转换或超时），之后不再监视
+   */
+  Failed,
+} FfiWatchEventKind;
 
 /**
- * 翻译任务类型（转写 / 总结）
+ * 客户端句柄，持有 token、默认超时和重试策略
+ * 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
  */
-typedef enum FfiTranslateTaskType {
-  Transcribe,
-  Summary,
-} FfiTranslateTaskType;
+typedef struct DianyaClient DianyaClient;
+
+/**
+ * 错误的结构化详情，随 `FfiError` 分配，`transcribe_ffi_free_error` 时释放
+ *
+ * SDK 的错误类型只携带错误信息字符串（`HttpError` / `WsError` 内部的错误也只能格式化为字符串），
+ * 因此 HTTP 状态码、请求 ID 和 Retry-After 都是从错误信息中按常见格式解析的，
+ * 格式不符时保持为 0 / None。
+ *
+ * 注意：这是一个不透明的指针类型，C 代码应通过 `transcribe_ffi_error_details` 读取
+ */
+typedef struct ErrorDetails ErrorDetails;
 
 typedef struct FfiCallbackRequest FfiCallbackRequest;
 
 /**
- * WebSocket 连接信息，包含连接实例和订阅流
+ * 可取消的长时间操作句柄
  * 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
  */
-typedef struct TranscribeStream TranscribeStream;
+typedef struct FfiOperation FfiOperation;
 
 /**
- * FFI 错误信息结构体
+ * 重试策略（仅作用于幂等请求）
  */
-typedef struct FfiError {
-  /**
-   * 错误码
-   */
-  enum ErrorCode code;
-  /**
-   * 错误信息（C 字符串，可能为 null）
-   */
-  char *message;
-} FfiError;
+typedef struct RetryPolicy RetryPolicy;
 
 /**
- * 分享链接结果
+ * 任务监视器句柄
+ * 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
  */
-typedef struct FfiShareLink {
-  char *share_url;
-  int32_t expiration_day;
-  char *expired_at;
-} FfiShareLink;
+typedef struct TaskWatcher TaskWatcher;
+
+/**
+ * WebSocket 连接信息，包含连接实例和订阅流
+ * 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
+ */
+typedef struct TranscribeStream TranscribeStream;
 
 /**
  * Utterance 映射到 C 结构体
@@ -89,40 +177,189 @@ typedef struct FfiUtterance {
 } FfiUtterance;
 
 /**
- * 回调历史
+ * 具体翻译详情（单条）
  */
-typedef struct FfiCallbackHistory {
-  char *timestamp;
-  char *status;
-  uint32_t code;
-} FfiCallbackHistory;
+typedef struct FfiTranslateDetail {
+  struct FfiUtterance utterance;
+  char *translation;
+} FfiTranslateDetail;
 
 /**
- * 转写状态
+ * 转写翻译结果
  */
-typedef struct FfiTranscribeStatus {
+typedef struct FfiTranscribeTranslator {
+  char *task_id;
+  enum FfiTranslateTaskType task_type;
   char *status;
+  enum FfiLanguage lang;
+  char *message;
+  struct FfiTranslateDetail *details;
+  uintptr_t details_len;
   char *overview_md;
   char *summary_md;
-  struct FfiUtterance *details;
-  uintptr_t details_len;
-  char *message;
-  char *usage_id;
-  char *task_id;
   char **keywords;
   uintptr_t keywords_len;
-  struct FfiCallbackHistory *callback_history;
-  uintptr_t callback_history_len;
-  enum FfiTranscribeTaskType task_type;
-  bool has_task_type;
-} FfiTranscribeStatus;
+} FfiTranscribeTranslator;
 
 /**
- * 创建总结任务结果
+ * 双语渲染选项，所有字段为 0 / null 时与默认选项相同
+ *
+ * 调用方须先用 `transcribe_ffi_bilingual_options_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiBilingualOptions)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
  */
-typedef struct FfiSummaryCreator {
-  char *task_id;
-} FfiSummaryCreator;
+typedef struct FfiBilingualOptions {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 输出格式（C 字符串）："srt"、"vtt"、"markdown" 或 "text"，为 null 时使用 "srt"
+   */
+  const char *format;
+  /**
+   * 说话人标签（C 字符串，可为 null），其中的 "{}" 替换为说话人编号，例如 "Speaker {}"；
+   * 为 null 时不标注说话人
+   */
+  const char *speaker_label;
+  /**
+   * 译文是否在原文之前（字幕中的行序，表格中的列序）
+   */
+  bool translation_first;
+  /**
+   * Markdown 和纯文本中是否包含开始时间
+   */
+  bool timestamps;
+  /**
+   * Markdown 表格原文列标题（C 字符串，可为 null，默认 "Original"）
+   */
+  const char *original_header;
+  /**
+   * Markdown 表格译文列标题（C 字符串，可为 null，默认 "Translation"）
+   */
+  const char *translation_header;
+  /**
+   * 字幕每行最大字符数，超出时折行，0 表示不折行
+   */
+  uintptr_t max_line_length;
+  /**
+   * 字幕的最短显示时间（毫秒）
+   */
+  uint64_t min_duration_ms;
+  /**
+   * 所有时间的偏移（毫秒），可为负
+   */
+  int64_t offset_ms;
+} FfiBilingualOptions;
+
+/**
+ * FFI 错误信息结构体
+ */
+typedef struct FfiError {
+  /**
+   * 错误码
+   */
+  enum ErrorCode code;
+  /**
+   * 错误信息（C 字符串，可能为 null）
+   */
+  char *message;
+  /**
+   * 结构化详情（可能为 null），通过 `transcribe_ffi_error_details` 读取
+   */
+  struct ErrorDetails *details;
+} FfiError;
+
+/**
+ * Utterance 翻译结果
+ */
+typedef struct FfiUtteranceTranslator {
+  char *status;
+  enum FfiLanguage lang;
+  struct FfiUtterance *details;
+  uintptr_t details_len;
+} FfiUtteranceTranslator;
+
+/**
+ * 重试策略配置
+ *
+ * 调用方须先用 `transcribe_ffi_retry_policy_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiRetryPolicy)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按 0 处理。
+ */
+typedef struct FfiRetryPolicy {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 最大尝试次数（包括首次请求），0 和 1 都表示不重试
+   */
+  uint32_t max_attempts;
+  /**
+   * 首次重试前的等待时间（毫秒），之后每次翻倍
+   */
+  uint64_t base_delay_ms;
+  /**
+   * 等待时间上限（毫秒），服务端返回的 Retry-After 也不会超过该值
+   */
+  uint64_t max_delay_ms;
+  /**
+   * 是否在 0 到计算出的等待时间之间随机取值
+   */
+  bool jitter;
+  /**
+   * 可重试的错误码数组，为 null 时重试网络错误、超时、限流和 5xx 错误
+   */
+  const int *retryable_codes;
+  /**
+   * 可重试的错误码数量
+   */
+  uintptr_t retryable_codes_len;
+} FfiRetryPolicy;
+
+/**
+ * 错误的结构化详情
+ *
+ * `http_status`、`request_id` 和 `retry_after_ms` 是从错误信息中尽力解析的，不保证存在，
+ * 调用方不应依赖它们做关键判断；`retryable` 由错误码和解析出的状态码推断。
+ * SDK 的错误不携带服务端的业务错误码，因此这里不提供 server_code，需要时请查看错误信息。
+ */
+typedef struct FfiErrorDetails {
+  /**
+   * HTTP 状态码（从错误信息中解析，0 表示未知）
+   */
+  uint16_t http_status;
+  /**
+   * 请求 ID / trace ID（从错误信息中解析，可能为 null）
+   */
+  char *request_id;
+  /**
+   * 是否值得重试（网络错误、408、429、5xx）
+   */
+  bool retryable;
+  /**
+   * 服务端建议的重试等待时间（毫秒，从错误信息中解析），0 表示未提供
+   */
+  uint64_t retry_after_ms;
+  /**
+   * 错误码为 `BufferTooSmall` 时所需的缓冲区大小
+   */
+  uintptr_t required_size;
+  /**
+   * 请求的尝试次数（包括首次请求，经过重试逻辑的请求才会填写），0 表示未知
+   */
+  uint32_t attempts;
+} FfiErrorDetails;
+
+/**
+ * 日志回调
+ *
+ * 参数依次为日志级别、来源模块、日志内容（已脱敏）和 user_data，字符串只在回调期间有效。
+ * 回调可能在任意线程（包括 runtime 工作线程）上调用。
+ */
+typedef void (*FfiLogCallback)(enum FfiLogLevel level,
+                               const char *target,
+                               const char *message,
+                               void *user_data);
 
 /**
  * 上传结果 - 普通模式
@@ -150,47 +387,364 @@ typedef struct FfiUploadResponse {
 } FfiUploadResponse;
 
 /**
- * 文本翻译结果
- */
-typedef struct FfiTextTranslator {
-  char *status;
-  char *data;
-} FfiTextTranslator;
-
-/**
- * Utterance 翻译结果
+ * panic 回调
+ *
+ * 参数依次为 panic 信息、源码位置（"文件:行:列"，可能为 null）、
+ * 回溯（未启用时为 null）和 user_data，字符串只在回调期间有效。
+ * 回调可能在任意线程（包括 runtime 工作线程）上调用。
  */
-typedef struct FfiUtteranceTranslator {
-  char *status;
-  enum FfiLanguage lang;
-  struct FfiUtterance *details;
-  uintptr_t details_len;
-} FfiUtteranceTranslator;
+typedef void (*FfiPanicCallback)(const char *message,
+                                 const char *location,
+                                 const char *backtrace,
+                                 void *user_data);
 
 /**
- * 具体翻译详情（单条）
+ * 上传选项
+ *
+ * 调用方须先用 `transcribe_ffi_upload_options_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiUploadOptions)` 并将其余字段清零）。新字段只会追加在末尾，使用旧版本头文件
+ * 编译的调用方传入较小的 `struct_size`，未覆盖的字段按默认值处理。
  */
-typedef struct FfiTranslateDetail {
-  struct FfiUtterance utterance;
-  char *translation;
-} FfiTranslateDetail;
+typedef struct FfiUploadOptions {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 模型类型字符串（"speed", "quality", "quality_v2"），不能为 null
+   */
+  const char *model;
+  /**
+   * 是否仅转写
+   */
+  bool transcribe_only;
+  /**
+   * 是否使用一句话转写模式
+   */
+  bool short_asr;
+} FfiUploadOptions;
 
 /**
- * 转写翻译结果
+ * 状态轮询配置
+ *
+ * 调用方须先用 `transcribe_ffi_poll_config_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiPollConfig)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
  */
-typedef struct FfiTranscribeTranslator {
-  char *task_id;
-  enum FfiTranslateTaskType task_type;
-  char *status;
-  enum FfiLanguage lang;
-  char *message;
-  struct FfiTranslateDetail *details;
-  uintptr_t details_len;
+typedef struct FfiPollConfig {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 首次轮询间隔（毫秒），0 表示使用默认值 2000
+   */
+  uint64_t initial_interval_ms;
+  /**
+   * 最大轮询间隔（毫秒），0 表示使用默认值 30000
+   */
+  uint64_t max_interval_ms;
+  /**
+   * 状态未变化时轮询间隔的增长倍数，小于 1 时使用默认值 1.5
+   */
+  double backoff_factor;
+  /**
+   * 总超时时间（毫秒），0 表示使用默认值 7200000（2 小时），`UINT64_MAX` 表示不设超时
+   */
+  uint64_t timeout_ms;
+  /**
+   * 终止状态数组（C 字符串数组，不区分大小写），为 null 时使用默认值：
+   * "done"、"completed"、"complete"、"finished"、"success"、"succeeded"、
+   * "failed"、"failure"、"error"、"cancelled"、"canceled"。
+   * 默认值是猜测的常见写法而非服务端约定，建议显式列出服务端实际使用的终止状态；
+   * 状态不在列表中时会一直轮询到超时
+   */
+  const char *const *terminal_statuses;
+  /**
+   * 终止状态数量
+   */
+  uintptr_t terminal_statuses_len;
+  /**
+   * 表示失败的终止状态数组（C 字符串数组，不区分大小写），任务监视器据此区分
+   * `Completed` 和 `Failed` 事件。为 null 时使用默认值：
+   * "failed"、"failure"、"error"、"cancelled"、"canceled"。
+   * 自定义 `terminal_statuses` 时通常也需要一并设置
+   */
+  const char *const *failure_statuses;
+  /**
+   * 失败状态数量
+   */
+  uintptr_t failure_statuses_len;
+} FfiPollConfig;
+
+/**
+ * 任务状态变化回调
+ *
+ * 参数依次为任务ID、新状态和 user_data，字符串只在回调期间有效。返回非 0 值将取消等待。
+ * 回调期间本库的同步接口不可用（返回 `InvalidInput` 错误）。
+ */
+typedef int (*FfiTaskStatusCallback)(const char *task_id, const char *status, void *user_data);
+
+/**
+ * 回调历史
+ */
+typedef struct FfiCallbackHistory {
+  char *timestamp;
+  char *status;
+  uint32_t code;
+} FfiCallbackHistory;
+
+/**
+ * 转写状态
+ */
+typedef struct FfiTranscribeStatus {
+  char *status;
   char *overview_md;
   char *summary_md;
+  struct FfiUtterance *details;
+  uintptr_t details_len;
+  char *message;
+  char *usage_id;
+  char *task_id;
   char **keywords;
   uintptr_t keywords_len;
-} FfiTranscribeTranslator;
+  struct FfiCallbackHistory *callback_history;
+  uintptr_t callback_history_len;
+  enum FfiTranscribeTaskType task_type;
+  bool has_task_type;
+} FfiTranscribeStatus;
+
+/**
+ * 说话人编号与名称的对应
+ */
+typedef struct FfiSpeakerName {
+  int32_t speaker;
+  /**
+   * 说话人名称（C 字符串）
+   */
+  const char *name;
+} FfiSpeakerName;
+
+/**
+ * utterance 后处理选项，所有字段为 0 / null 时不做任何处理
+ *
+ * 调用方须先用 `transcribe_ffi_utterance_process_options_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiUtteranceProcessOptions)` 并将其余字段清零）。新字段只会追加在末尾，
+ * 未覆盖的字段按默认值处理。
+ */
+typedef struct FfiUtteranceProcessOptions {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 是否合并同一说话人的相邻 utterance
+   */
+  bool merge;
+  /**
+   * 合并时允许的最大间隔（毫秒），即前一句结束到后一句开始的时间
+   */
+  uint64_t merge_gap_ms;
+  /**
+   * 每条 utterance 的最大字符数，超出时在标点处拆分并按字数插值时间，0 表示不拆分
+   */
+  uintptr_t max_chars;
+  /**
+   * 说话人名称数组（可为 null），未列出的说话人名称为 null，同一说话人不能重复出现
+   */
+  const struct FfiSpeakerName *speaker_names;
+  /**
+   * 说话人名称数量
+   */
+  uintptr_t speaker_names_len;
+} FfiUtteranceProcessOptions;
+
+/**
+ * 后处理后的 utterance
+ */
+typedef struct FfiProcessedUtterance {
+  struct FfiUtterance utterance;
+  /**
+   * 说话人名称（C 字符串），未提供对应名称时为 null
+   */
+  char *speaker_name;
+} FfiProcessedUtterance;
+
+/**
+ * 后处理结果
+ */
+typedef struct FfiProcessedUtterances {
+  struct FfiProcessedUtterance *items;
+  uintptr_t items_len;
+} FfiProcessedUtterances;
+
+/**
+ * runtime 配置
+ *
+ * 调用方须先用 `transcribe_ffi_runtime_config_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiRuntimeConfig)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+ */
+typedef struct FfiRuntimeConfig {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 工作线程数，0 表示使用默认值 4（current_thread 模式下忽略）
+   */
+  uintptr_t worker_threads;
+  /**
+   * 线程名前缀（C 字符串，可为 null，默认 "dianyaapi-ffi"）
+   */
+  const char *thread_name_prefix;
+  /**
+   * 线程栈大小（字节），0 表示使用默认值
+   */
+  uintptr_t thread_stack_size;
+  /**
+   * 是否使用单线程 runtime，任务在一个独立的驱动线程上执行
+   */
+  bool current_thread;
+} FfiRuntimeConfig;
+
+/**
+ * 单个说话人的统计
+ */
+typedef struct FfiSpeakerStat {
+  /**
+   * 说话人编号
+   */
+  int32_t speaker;
+  /**
+   * 发言总时长（秒）
+   */
+  double talk_time;
+  /**
+   * 发言时长占所有已识别说话人发言总时长的比例（0 ~ 1）
+   */
+  double talk_ratio;
+  /**
+   * 发言轮次（连续的同一说话人 utterance 计为一轮）
+   */
+  uintptr_t turns;
+  /**
+   * 打断次数（在其他说话人的发言结束前开始发言）
+   */
+  uintptr_t interruptions;
+  /**
+   * 最长的一轮连续发言（秒）
+   */
+  double longest_monologue;
+  /**
+   * 词数（中日韩文字按字计数，其他文字按空白和标点分词）
+   */
+  uintptr_t words;
+  /**
+   * 每分钟词数（按发言时长计算）
+   */
+  double words_per_minute;
+} FfiSpeakerStat;
+
+/**
+ * 说话人统计结果
+ */
+typedef struct FfiSpeakerStats {
+  /**
+   * 各说话人的统计，按说话人编号升序排列，不包括未识别的说话人（编号为负）
+   */
+  struct FfiSpeakerStat *speakers;
+  uintptr_t speakers_len;
+  /**
+   * 从第一句开始到最后一句结束的总时长（秒）
+   */
+  double total_time;
+  /**
+   * 其间无人发言的总时长（秒）
+   */
+  double silence_time;
+  /**
+   * 其间两个及以上已识别的说话人同时发言的总时长（秒）
+   */
+  double overlap_time;
+} FfiSpeakerStats;
+
+/**
+ * 字幕渲染选项，所有字段为 0 / null 时与默认选项相同
+ *
+ * 调用方须先用 `transcribe_ffi_subtitle_options_init` 初始化（或将 `struct_size` 设为
+ * `sizeof(FfiSubtitleOptions)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+ */
+typedef struct FfiSubtitleOptions {
+  /**
+   * 结构体大小（字节）
+   */
+  uintptr_t struct_size;
+  /**
+   * 字幕格式（C 字符串，"srt" 或 "vtt"，为 null 时使用 "srt"）
+   */
+  const char *format;
+  /**
+   * 说话人标签（C 字符串，可为 null），其中的 "{}" 替换为说话人编号，例如 "Speaker {}"。
+   * SRT 中作为 "标签: " 前缀，WebVTT 中作为 `<v 标签>` 声音标记；为 null 时不标注说话人
+   */
+  const char *speaker_label;
+  /**
+   * 每行最大字符数，超出时折行（优先在空格和标点处），0 表示不折行
+   */
+  uintptr_t max_line_length;
+  /**
+   * 每条字幕的最大行数，超出时按字数比例拆分为多条字幕，0 表示不限制
+   */
+  uintptr_t max_lines;
+  /**
+   * 每条字幕的最短显示时间（毫秒），不足时延长结束时间（不与下一条重叠）
+   */
+  uint64_t min_duration_ms;
+  /**
+   * 所有时间的偏移（毫秒），可为负，偏移后结束时间不大于 0 的字幕会被丢弃
+   */
+  int64_t offset_ms;
+} FfiSubtitleOptions;
+
+/**
+ * token 提供回调
+ *
+ * 参数为失效的 token 和 user_data，返回新的 token（C 字符串，本库会立即复制，
+ * 所有权仍归宿主），无法提供时返回 null。回调可能在任意线程（包括 runtime 工作线程）上调用，
+ * 同一时刻最多只有一个调用。
+ */
+typedef const char *(*FfiTokenProvider)(const char *expired_token, void *user_data);
+
+/**
+ * 由本库分配的二进制数据
+ */
+typedef struct FfiBytes {
+  uint8_t *ptr;
+  uintptr_t len;
+} FfiBytes;
+
+/**
+ * 分享链接结果
+ */
+typedef struct FfiShareLink {
+  char *share_url;
+  int32_t expiration_day;
+  char *expired_at;
+} FfiShareLink;
+
+/**
+ * 创建总结任务结果
+ */
+typedef struct FfiSummaryCreator {
+  char *task_id;
+} FfiSummaryCreator;
+
+/**
+ * 文本翻译结果
+ */
+typedef struct FfiTextTranslator {
+  char *status;
+  char *data;
+} FfiTextTranslator;
 
 /**
  * 转写状态回调响应
@@ -199,6 +753,79 @@ typedef struct FfiCallbackResponse {
   char *status;
 } FfiCallbackResponse;
 
+/**
+ * 上传完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiUploadCallback)(uint64_t op_id,
+                                  int code,
+                                  const struct FfiUploadResponse *result,
+                                  const struct FfiError *error,
+                                  void *user_data);
+
+/**
+ * 获取状态完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiStatusCallback)(uint64_t op_id,
+                                  int code,
+                                  const struct FfiTranscribeStatus *result,
+                                  const struct FfiError *error,
+                                  void *user_data);
+
+/**
+ * 导出完成回调
+ *
+ * `data` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiExportCallback)(uint64_t op_id,
+                                  int code,
+                                  const uint8_t *data,
+                                  uintptr_t data_len,
+                                  const struct FfiError *error,
+                                  void *user_data);
+
+/**
+ * 文本翻译完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiTextTranslatorCallback)(uint64_t op_id,
+                                          int code,
+                                          const struct FfiTextTranslator *result,
+                                          const struct FfiError *error,
+                                          void *user_data);
+
+/**
+ * Utterance 翻译完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiUtteranceTranslatorCallback)(uint64_t op_id,
+                                               int code,
+                                               const struct FfiUtteranceTranslator *result,
+                                               const struct FfiError *error,
+                                               void *user_data);
+
+/**
+ * 转写翻译完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiTranscribeTranslatorCallback)(uint64_t op_id,
+                                                int code,
+                                                const struct FfiTranscribeTranslator *result,
+                                                const struct FfiError *error,
+                                                void *user_data);
+
 /**
  * Session 创建结果
  */
@@ -209,6 +836,18 @@ typedef struct FfiSessionCreator {
   int32_t max_time;
 } FfiSessionCreator;
 
+/**
+ * 创建会话完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiSessionCreatorCallback)(uint64_t op_id,
+                                          int code,
+                                          const struct FfiSessionCreator *result,
+                                          const struct FfiError *error,
+                                          void *user_data);
+
 /**
  * Session 关闭结果
  */
@@ -221,177 +860,1282 @@ typedef struct FfiSessionEnder {
   char *message;
 } FfiSessionEnder;
 
-void transcribe_ffi_free_error(struct FfiError *e);
+/**
+ * 关闭会话完成回调
+ *
+ * `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+ * 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+ */
+typedef void (*FfiSessionEnderCallback)(uint64_t op_id,
+                                        int code,
+                                        const struct FfiSessionEnder *result,
+                                        const struct FfiError *error,
+                                        void *user_data);
 
 /**
- * 导出转写内容或总结内容
+ * 上传数据读取回调
+ *
+ * 向 `buf` 写入最多 `buf_len` 字节，返回写入的字节数；返回 0 表示数据结束，
+ * 返回负值将中止上传
+ */
+typedef intptr_t (*FfiReadCallback)(uint8_t *buf, uintptr_t buf_len, void *user_data);
+
+/**
+ * 任务事件
+ */
+typedef struct FfiWatchEvent {
+  /**
+   * 事件类型
+   */
+  enum FfiWatchEventKind kind;
+  /**
+   * 任务ID（C 字符串）
+   */
+  char *task_id;
+  /**
+   * 任务状态，查询出错时为 null
+   */
+  struct FfiTranscribeStatus *status;
+  /**
+   * 查询错误，有状态时为 null
+   */
+  struct FfiError *error;
+} FfiWatchEvent;
+
+/**
+ * 任务事件回调
+ *
+ * 事件只在回调期间有效，回调返回后由本库释放。回调在 runtime 工作线程上调用，
+ * 不能在回调中调用 `transcribe_ffi_watcher_free` 或同步请求接口（后者返回 `InvalidInput` 错误）；
+ * `transcribe_ffi_watcher_free` 返回后不会再调用回调。
+ */
+typedef void (*FfiWatchCallback)(const struct FfiWatchEvent *event, void *user_data);
+
+
+
+/**
+ * 将转写翻译结果渲染为双语字幕、Markdown 表格或纯文本
+ *
+ * 在本地渲染，不请求服务端，时间单位为秒。
  *
  * # 参数
- * - `task_id`: 任务ID（C 字符串）
- * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
- * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
- * - `token`: Bearer token（C 字符串）
- * - `result_data`: 输出二进制数据的缓冲区指针
- * - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+ * - `translator`: `transcribe_ffi_translate_transcribe` 返回的翻译结果
+ * - `options`: 渲染选项，为 null 时使用默认选项（SRT，原文在前，不标注说话人）
+ * - `out_text`: 输出文本指针，需调用 `transcribe_ffi_free_string` 释放
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
  * 错误码（0 表示成功）
  */
-int transcribe_ffi_export(const char *task_id,
-                          const char *export_type,
-                          const char *export_format,
-                          const char *token,
-                          uint8_t *result_data,
-                          uintptr_t *result_len,
-                          struct FfiError *out_error);
+int transcribe_ffi_render_transcribe_translation(const struct FfiTranscribeTranslator *translator,
+                                                 const struct FfiBilingualOptions *options,
+                                                 char **out_text,
+                                                 struct FfiError *out_error);
+
+/**
+ * 将 utterance 翻译结果与原文一起渲染为双语字幕、Markdown 表格或纯文本
+ *
+ * `utterances` 为调用 `transcribe_ffi_translate_utterance` 时传入的原文，
+ * 与翻译结果按顺序一一对应，时间取自原文。
+ *
+ * # 参数
+ * - `utterances`: 原文 utterance 数组指针
+ * - `utterances_len`: 原文 utterance 数量，需与翻译结果数量一致
+ * - `translator`: `transcribe_ffi_translate_utterance` 返回的翻译结果
+ * - `options`: 渲染选项，为 null 时使用默认选项（SRT，原文在前，不标注说话人）
+ * - `out_text`: 输出文本指针，需调用 `transcribe_ffi_free_string` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_render_utterance_translation(const struct FfiUtterance *utterances,
+                                                uintptr_t utterances_len,
+                                                const struct FfiUtteranceTranslator *translator,
+                                                const struct FfiBilingualOptions *options,
+                                                char **out_text,
+                                                struct FfiError *out_error);
+
+/**
+ * 初始化双语渲染选项
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `options`: 双语渲染选项结构体指针
+ */
+void transcribe_ffi_bilingual_options_init(struct FfiBilingualOptions *options);
+
+/**
+ * 创建客户端句柄
+ *
+ * # 参数
+ * - `token`: Bearer token（C 字符串）
+ * - `out_client`: 输出客户端句柄指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_client_create(const char *token,
+                                 struct DianyaClient **out_client,
+                                 struct FfiError *out_error);
+
+/**
+ * 更新客户端的 Bearer token
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `token`: Bearer token（C 字符串）
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_client_set_token(const struct DianyaClient *client,
+                                    const char *token,
+                                    struct FfiError *out_error);
+
+/**
+ * 设置客户端请求的默认超时时间
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `timeout_ms`: 超时时间（毫秒），0 表示不设超时
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_client_set_timeout(const struct DianyaClient *client,
+                                      uint64_t timeout_ms,
+                                      struct FfiError *out_error);
+
+/**
+ * 设置客户端的重试策略
+ *
+ * 仅对幂等请求（获取状态、导出、获取翻译结果）生效，且只在网络错误、超时、限流和 5xx 错误时重试。
+ * 等同于以固定间隔调用 `transcribe_ffi_client_set_retry_config`。
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `max_retries`: 最大重试次数（0 表示不重试）
+ * - `retry_delay_ms`: 两次重试之间的间隔（毫秒）
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_client_set_retry_policy(const struct DianyaClient *client,
+                                           uint32_t max_retries,
+                                           uint64_t retry_delay_ms,
+                                           struct FfiError *out_error);
+
+/**
+ * 设置客户端的重试策略（指数退避）
+ *
+ * 仅对幂等请求（获取状态、导出、获取分享链接、获取转写翻译结果）生效，
+ * 上传、创建总结、文本与 utterance 翻译等请求不会重试。
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `policy`: 重试策略，为 null 时不重试
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_client_set_retry_config(const struct DianyaClient *client,
+                                           const struct FfiRetryPolicy *policy,
+                                           struct FfiError *out_error);
+
+/**
+ * 释放客户端句柄
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ */
+void transcribe_ffi_client_free(struct DianyaClient *client);
+
+void transcribe_ffi_free_error(struct FfiError *e);
+
+/**
+ * 获取错误的结构化详情
+ *
+ * 详情随错误一起保存，从 SDK 返回的错误信息中尽力解析，无法解析的字段保持为 0 / null。
+ * 需在 `transcribe_ffi_free_error` 之前调用，结果需调用 `transcribe_ffi_free_error_details` 释放。
+ *
+ * # 参数
+ * - `error`: 错误信息结构体指针
+ * - `out_details`: 输出错误详情结构体指针
+ *
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_error_details(const struct FfiError *error,
+                                 struct FfiErrorDetails *out_details);
+
+void transcribe_ffi_free_error_details(struct FfiErrorDetails *d);
+
+/**
+ * 获取当前线程最近一次失败调用的错误码
+ *
+ * 与 errno 类似，成功的调用不会清除最近错误。
+ *
+ * # 返回
+ * 错误码，没有错误时返回 0
+ */
+int transcribe_ffi_last_error_code(void);
+
+/**
+ * 获取当前线程最近一次失败调用的错误信息
+ *
+ * 返回的字符串归本库所有，无需释放，在当前线程下一次失败调用或
+ * `transcribe_ffi_clear_last_error` 之前有效。
+ *
+ * # 返回
+ * 错误信息（C 字符串），没有错误时返回 null
+ */
+const char *transcribe_ffi_last_error_message(void);
+
+/**
+ * 获取当前线程最近一次失败调用的错误详情
+ *
+ * 与 `transcribe_ffi_error_details` 相同，结果需调用 `transcribe_ffi_free_error_details` 释放。
+ *
+ * # 参数
+ * - `out_details`: 输出错误详情结构体指针
+ *
+ * # 返回
+ * 错误码（0 表示成功，没有最近错误时所有字段为 0 / null）
+ */
+int transcribe_ffi_last_error_details(struct FfiErrorDetails *out_details);
+
+/**
+ * 清除当前线程的最近错误
+ */
+void transcribe_ffi_clear_last_error(void);
+
+/**
+ * 设置日志回调
+ *
+ * 本库、运行时以及底层 SDK 的日志都会转发给回调，token 等敏感信息会被隐藏。
+ * 依赖中通过 `tracing` 输出的事件在进程内没有 tracing subscriber 时会转为日志一并转发。
+ * 宿主进程中已有其他 Rust logger 时无法安装，返回错误。
+ *
+ * # 参数
+ * - `level`: 最低日志级别
+ * - `callback`: 日志回调，传 null 取消
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_set_log_callback(enum FfiLogLevel level,
+                                    FfiLogCallback callback,
+                                    void *user_data,
+                                    struct FfiError *out_error);
+
+/**
+ * 调整日志级别
+ *
+ * # 参数
+ * - `level`: 最低日志级别
+ */
+void transcribe_ffi_set_log_level(enum FfiLogLevel level);
+
+/**
+ * 获取统计指标快照（JSON）
+ *
+ * 以操作名（"upload"、"get_status"、"export"、"translate_text"、"translate_utterance"、
+ * "translate_transcribe"、"ws_write_txt"、"ws_write_bytes"、"ws_receive"）为键，每项包含
+ * 请求数 `requests`、按错误码名统计的错误数 `errors`、延迟（微秒）`latency_total_us` /
+ * `latency_avg_us` / `latency_max_us`，以及 `bytes_sent`、`bytes_received`、
+ * `messages_sent`、`messages_received`。
+ *
+ * # 参数
+ * - `out_json`: 输出 JSON 字符串指针，需调用 `transcribe_ffi_free_string` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_metrics_snapshot(char **out_json,
+                                    struct FfiError *out_error);
+
+/**
+ * 清零所有统计指标
+ */
+void transcribe_ffi_metrics_reset(void);
+
+/**
+ * 取消操作
+ *
+ * 正在执行的请求会在 runtime 上被丢弃，之后获取结果将返回 `Cancelled` 错误。
+ * 对已结束的操作调用无效果。
+ *
+ * # 参数
+ * - `op`: 操作句柄
+ */
+void transcribe_ffi_op_cancel(const struct FfiOperation *op);
+
+/**
+ * 查询操作状态（不阻塞）
+ *
+ * # 参数
+ * - `op`: 操作句柄
+ *
+ * # 返回
+ * 操作状态；句柄为 null 时返回 `Failed`
+ */
+enum FfiOperationState transcribe_ffi_op_poll(const struct FfiOperation *op);
+
+/**
+ * 等待操作结束
+ *
+ * # 参数
+ * - `op`: 操作句柄
+ * - `timeout_ms`: 超时时间（毫秒），0 表示一直等待
+ *
+ * # 返回
+ * 等待结束时的操作状态；超时返回 `Pending`，句柄为 null 时返回 `Failed`
+ */
+enum FfiOperationState transcribe_ffi_op_wait(const struct FfiOperation *op, uint64_t timeout_ms);
+
+/**
+ * 获取上传操作的结果
+ *
+ * 结果只能获取一次，成功后需调用 `transcribe_ffi_free_upload_response` 释放
+ *
+ * # 参数
+ * - `op`: 由 `transcribe_ffi_upload_start` 返回的操作句柄
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功，操作被取消时为 `Cancelled`）
+ */
+int transcribe_ffi_op_upload_result(const struct FfiOperation *op,
+                                    struct FfiUploadResponse *out_result,
+                                    struct FfiError *out_error);
+
+/**
+ * 获取导出操作的结果
+ *
+ * 缓冲区不足时返回错误并在 `result_len` 中写入所需大小，
+ * 数据仍保留在操作中，可用更大的缓冲区再次获取。
+ *
+ * # 参数
+ * - `op`: 由 `transcribe_ffi_export_start` 返回的操作句柄
+ * - `result_data`: 输出二进制数据的缓冲区指针
+ * - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功，操作被取消时为 `Cancelled`）
+ */
+int transcribe_ffi_op_export_result(const struct FfiOperation *op,
+                                    uint8_t *result_data,
+                                    uintptr_t *result_len,
+                                    struct FfiError *out_error);
+
+/**
+ * 释放操作句柄，仍在执行的操作会被取消
+ *
+ * # 参数
+ * - `op`: 操作句柄
+ */
+void transcribe_ffi_op_free(struct FfiOperation *op);
+
+/**
+ * 设置是否在 panic 错误信息中附带回溯
+ *
+ * 未调用时由环境变量 `DIANYAAPI_FFI_BACKTRACE` 决定（"1" 或 "true" 时启用）。
+ *
+ * # 参数
+ * - `enabled`: 是否启用
+ */
+void transcribe_ffi_set_panic_backtrace(bool enabled);
+
+/**
+ * 设置 panic 回调，本库内任何线程发生 panic 时都会调用
+ *
+ * # 参数
+ * - `callback`: panic 回调，传 null 取消
+ * - `user_data`: 原样传给回调的用户数据
+ */
+void transcribe_ffi_set_panic_callback(FfiPanicCallback callback, void *user_data);
+
+/**
+ * 上传音频文件并等待转写完成
+ *
+ * 上传后按 `poll_config` 轮询任务状态（状态未变化时逐步拉长间隔），直到状态进入终止状态，
+ * 并输出最终状态（包括失败状态，调用方需检查 `status` 字段）。回调在当前线程上调用，
+ * 返回非 0 值时停止等待并返回 `Cancelled` 错误；超过总超时时间（默认 2 小时）返回 `Timeout` 错误。
+ * 一句话转写模式（`short_asr`）的上传直接返回结果，不支持本函数。
+ *
+ * # 参数
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `options`: 上传选项
+ * - `token`: Bearer token（C 字符串）
+ * - `poll_config`: 轮询配置，为 null 时使用默认配置
+ * - `progress_cb`: 状态变化回调（可为 null）
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_status`: 输出最终状态结构体指针，需调用 `transcribe_ffi_free_transcribe_status` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_transcribe_file(const char *filepath,
+                                   const struct FfiUploadOptions *options,
+                                   const char *token,
+                                   const struct FfiPollConfig *poll_config,
+                                   FfiTaskStatusCallback progress_cb,
+                                   void *user_data,
+                                   struct FfiTranscribeStatus *out_status,
+                                   struct FfiError *out_error);
+
+/**
+ * 初始化状态轮询配置
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `config`: 轮询配置结构体指针
+ */
+void transcribe_ffi_poll_config_init(struct FfiPollConfig *config);
+
+/**
+ * 对 utterance 数组做后处理
+ *
+ * 依次合并同一说话人的相邻 utterance、在标点处拆分过长的 utterance，并为说话人标注名称。
+ * 在本地处理，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+ * `details` / `details_len`，时间单位为秒，按原顺序处理。
+ *
+ * # 参数
+ * - `utterances`: utterance 数组指针
+ * - `utterances_len`: utterance 数量
+ * - `options`: 后处理选项，为 null 时原样复制
+ * - `out_result`: 输出结果结构体指针，需调用 `transcribe_ffi_free_processed_utterances` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_process_utterances(const struct FfiUtterance *utterances,
+                                      uintptr_t utterances_len,
+                                      const struct FfiUtteranceProcessOptions *options,
+                                      struct FfiProcessedUtterances *out_result,
+                                      struct FfiError *out_error);
+
+void transcribe_ffi_free_processed_utterances(struct FfiProcessedUtterances *s);
+
+/**
+ * 初始化 utterance 后处理选项
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `options`: 后处理选项结构体指针
+ */
+void transcribe_ffi_utterance_process_options_init(struct FfiUtteranceProcessOptions *options);
+
+/**
+ * 设置全局重试策略
+ *
+ * 作用于不使用客户端句柄的幂等请求：获取状态、导出、获取分享链接和获取翻译结果
+ * （包括对应的异步接口）。默认不重试。
+ *
+ * # 参数
+ * - `policy`: 重试策略，为 null 时恢复为不重试
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_set_retry_policy(const struct FfiRetryPolicy *policy,
+                                    struct FfiError *out_error);
+
+/**
+ * 初始化重试策略配置
+ *
+ * 将所有字段清零（不重试），并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `policy`: 重试策略结构体指针
+ */
+void transcribe_ffi_retry_policy_init(struct FfiRetryPolicy *policy);
+
+/**
+ * 初始化全局 runtime
+ *
+ * 必须在其他调用之前（或 `transcribe_ffi_runtime_shutdown` 之后）调用，
+ * runtime 已存在时返回错误。未调用时首次使用会创建 4 个工作线程的默认 runtime。
+ * 关闭后只能通过本函数重新创建 runtime。
+ *
+ * # 参数
+ * - `config`: runtime 配置，为 null 时使用默认配置
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_runtime_init(const struct FfiRuntimeConfig *config,
+                                struct FfiError *out_error);
+
+/**
+ * 关闭全局 runtime
+ *
+ * 先等待正在执行的异步操作和其他线程上的同步调用结束，超时后取消剩余异步操作
+ * （其回调以 `Cancelled` 错误调用，操作句柄进入 `Cancelled` 状态），仍未返回的同步调用
+ * 以 `RuntimeShutDown` 或网络错误返回；然后关闭所有未释放的 WebSocket 连接。
+ * 任务监视器不计入等待的操作，runtime 关闭后直接停止。
+ * 不能在本库的回调中调用。
+ *
+ * 从开始关闭起，需要 runtime 的接口（网络请求、异步操作、WebSocket、任务监视器）
+ * 都返回 `RuntimeShutDown` 错误，直到再次调用 `transcribe_ffi_runtime_init`。
+ * 不需要 runtime 的接口仍可正常调用：各 `free` 函数、操作句柄的查询 / 等待 / 取消 /
+ * 取结果、错误信息、日志、指标、重试策略、token 提供者以及本地渲染和统计。
+ * runtime 未创建或已关闭时再次调用本函数直接返回成功。
+ *
+ * # 参数
+ * - `timeout_ms`: 等待正在执行的操作的超时时间（毫秒），0 表示立即取消
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_runtime_shutdown(uint64_t timeout_ms,
+                                    struct FfiError *out_error);
+
+/**
+ * 初始化 runtime 配置
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `config`: runtime 配置结构体指针
+ */
+void transcribe_ffi_runtime_config_init(struct FfiRuntimeConfig *config);
+
+/**
+ * 统计各说话人的发言情况
+ *
+ * 在本地计算，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+ * `details` / `details_len`，时间单位为秒，顺序不限。
+ * 未识别的说话人（编号为负）不出现在 `speakers` 中，但其发言时段不计入静默。
+ *
+ * # 参数
+ * - `utterances`: utterance 数组指针
+ * - `utterances_len`: utterance 数量
+ * - `out_stats`: 输出统计结构体指针，需调用 `transcribe_ffi_free_speaker_stats` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_speaker_stats(const struct FfiUtterance *utterances,
+                                 uintptr_t utterances_len,
+                                 struct FfiSpeakerStats *out_stats,
+                                 struct FfiError *out_error);
+
+void transcribe_ffi_free_speaker_stats(struct FfiSpeakerStats *s);
+
+/**
+ * 将 utterance 数组渲染为 SRT 或 WebVTT 字幕
+ *
+ * 在本地渲染，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+ * `details` / `details_len`，时间单位为秒。
+ *
+ * # 参数
+ * - `utterances`: utterance 数组指针
+ * - `utterances_len`: utterance 数量
+ * - `options`: 渲染选项，为 null 时使用默认选项（SRT，不标注说话人，不折行）
+ * - `out_text`: 输出字幕文本指针，需调用 `transcribe_ffi_free_string` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_render_subtitles(const struct FfiUtterance *utterances,
+                                    uintptr_t utterances_len,
+                                    const struct FfiSubtitleOptions *options,
+                                    char **out_text,
+                                    struct FfiError *out_error);
+
+/**
+ * 初始化字幕渲染选项
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `options`: 字幕渲染选项结构体指针
+ */
+void transcribe_ffi_subtitle_options_init(struct FfiSubtitleOptions *options);
+
+/**
+ * 设置 token 提供回调
+ *
+ * 请求因 token 失效（`InvalidToken` / `InvalidApiKey`）失败时，本库调用回调获取新 token
+ * 并透明地重试一次。之后使用旧 token 的调用（包括客户端句柄、实时转写会话的关闭等）
+ * 会自动改用新 token。
+ *
+ * # 参数
+ * - `provider`: token 提供回调，传 null 取消
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_set_token_provider(FfiTokenProvider provider,
+                                      void *user_data,
+                                      struct FfiError *out_error);
+
+/**
+ * 导出转写内容或总结内容
+ *
+ * 缓冲区不足时会返回错误并写入所需大小；如不想预先分配缓冲区，
+ * 请使用 `transcribe_ffi_export_bytes`。
+ *
+ * # 参数
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+ * - `token`: Bearer token（C 字符串）
+ * - `result_data`: 输出二进制数据的缓冲区指针
+ * - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_export(const char *task_id,
+                          const char *export_type,
+                          const char *export_format,
+                          const char *token,
+                          uint8_t *result_data,
+                          uintptr_t *result_len,
+                          struct FfiError *out_error);
+
+/**
+ * 导出转写内容或总结内容，数据由本库分配
+ *
+ * 与 `transcribe_ffi_export` 不同，无需预先知道数据大小，每次导出只请求一次服务器。
+ * 成功后需调用 `transcribe_ffi_free_bytes` 释放 `out_bytes`。
+ *
+ * # 参数
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_bytes`: 输出二进制数据结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_export_bytes(const char *task_id,
+                                const char *export_type,
+                                const char *export_format,
+                                const char *token,
+                                struct FfiBytes *out_bytes,
+                                struct FfiError *out_error);
+
+/**
+ * 导出转写内容或总结内容并写入文件
+ *
+ * 数据先写入同目录下的临时文件，完成后再替换目标文件，
+ * 失败时目标文件保持不变，不会留下不完整的文件。
+ *
+ * 注意：SDK 的导出接口一次性返回完整内容，不支持分块读取，因此整个文档仍会先缓存在
+ * 内存中再写入文件，内存占用与文档大小相同。本函数只省去了调用方分配缓冲区和写文件的步骤。
+ *
+ * # 参数
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+ * - `token`: Bearer token（C 字符串）
+ * - `path`: 目标文件路径（C 字符串）
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_export_to_file(const char *task_id,
+                                  const char *export_type,
+                                  const char *export_format,
+                                  const char *token,
+                                  const char *path,
+                                  struct FfiError *out_error);
+
+/**
+ * 获取转写分享链接
+ *
+ * # 参数
+ * - `task_id`: 任务ID（C 字符串）
+ * - `expiration_day`: 过期天数（0 表示使用默认值 7 天）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_link`: 输出分享链接结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_get_share_link(const char *task_id,
+                                  int32_t expiration_day,
+                                  const char *token,
+                                  struct FfiShareLink *out_link,
+                                  struct FfiError *out_error);
+
+/**
+ * 获取转写任务状态
+ *
+ * # 参数
+ * - `task_id`: 任务ID（可为 NULL，如果提供 share_id）
+ * - `share_id`: 分享链接ID（可为 NULL，如果提供 task_id）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_status`: 输出状态结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_get_status(const char *task_id,
+                              const char *share_id,
+                              const char *token,
+                              struct FfiTranscribeStatus *out_status,
+                              struct FfiError *out_error);
+
+/**
+ * 创建总结任务
+ *
+ * # 参数
+ * - `utterances`: Utterance 数组指针
+ * - `utterances_len`: Utterance 数组长度
+ * - `token`: Bearer token（C 字符串）
+ * - `out_summary`: 输出总结任务信息结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_create_summary(const struct FfiUtterance *utterances,
+                                  uintptr_t utterances_len,
+                                  const char *token,
+                                  struct FfiSummaryCreator *out_summary,
+                                  struct FfiError *out_error);
+
+/**
+ * 上传音频文件进行转写
+ *
+ * # 参数
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_upload(const char *filepath,
+                          bool transcribe_only,
+                          bool short_asr,
+                          const char *model,
+                          const char *token,
+                          struct FfiUploadResponse *out_result,
+                          struct FfiError *out_error);
+
+/**
+ * 翻译文本
+ *
+ * # 参数
+ * - `text`: 要翻译的文本（C 字符串）
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出文本翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_text(const char *text,
+                                  const char *target_lang,
+                                  const char *token,
+                                  struct FfiTextTranslator *out_result,
+                                  struct FfiError *out_error);
+
+/**
+ * 翻译 utterances 列表
+ *
+ * # 参数
+ * - `utterances`: Utterance 数组指针
+ * - `utterances_len`: Utterance 数组长度
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出 utterance 翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_utterance(const struct FfiUtterance *utterances,
+                                       uintptr_t utterances_len,
+                                       const char *target_lang,
+                                       const char *token,
+                                       struct FfiUtteranceTranslator *out_result,
+                                       struct FfiError *out_error);
+
+/**
+ * 获取转写任务的翻译结果
+ *
+ * # 参数
+ * - `task_id`: 任务ID（C 字符串）
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出转写翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_transcribe(const char *task_id,
+                                        const char *target_lang,
+                                        const char *token,
+                                        struct FfiTranscribeTranslator *out_result,
+                                        struct FfiError *out_error);
+
+/**
+ * 处理转写任务状态回调（服务器端使用）
+ *
+ * # 参数
+ * - `request`: 回调请求结构体指针
+ * - `token`: Bearer token（C 字符串）
+ * - `out_response`: 输出回调响应结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_callback(const struct FfiCallbackRequest *request,
+                            const char *token,
+                            struct FfiCallbackResponse *out_response,
+                            struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄上传音频文件进行转写
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_upload_with_client(const struct DianyaClient *client,
+                                      const char *filepath,
+                                      bool transcribe_only,
+                                      bool short_asr,
+                                      const char *model,
+                                      struct FfiUploadResponse *out_result,
+                                      struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄获取转写任务状态
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（可为 NULL，如果提供 share_id）
+ * - `share_id`: 分享链接ID（可为 NULL，如果提供 task_id）
+ * - `out_status`: 输出状态结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_get_status_with_client(const struct DianyaClient *client,
+                                          const char *task_id,
+                                          const char *share_id,
+                                          struct FfiTranscribeStatus *out_status,
+                                          struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄导出转写内容或总结内容
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+ * - `result_data`: 输出二进制数据的缓冲区指针
+ * - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_export_with_client(const struct DianyaClient *client,
+                                      const char *task_id,
+                                      const char *export_type,
+                                      const char *export_format,
+                                      uint8_t *result_data,
+                                      uintptr_t *result_len,
+                                      struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄导出转写内容或总结内容，数据由本库分配
+ *
+ * 成功后需调用 `transcribe_ffi_free_bytes` 释放 `out_bytes`。
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+ * - `out_bytes`: 输出二进制数据结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_export_bytes_with_client(const struct DianyaClient *client,
+                                            const char *task_id,
+                                            const char *export_type,
+                                            const char *export_format,
+                                            struct FfiBytes *out_bytes,
+                                            struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄翻译文本
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `text`: 要翻译的文本（C 字符串）
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `out_result`: 输出文本翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_text_with_client(const struct DianyaClient *client,
+                                              const char *text,
+                                              const char *target_lang,
+                                              struct FfiTextTranslator *out_result,
+                                              struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄翻译 utterances 列表
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `utterances`: Utterance 数组指针
+ * - `utterances_len`: Utterance 数组长度
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `out_result`: 输出 utterance 翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_utterance_with_client(const struct DianyaClient *client,
+                                                   const struct FfiUtterance *utterances,
+                                                   uintptr_t utterances_len,
+                                                   const char *target_lang,
+                                                   struct FfiUtteranceTranslator *out_result,
+                                                   struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄获取转写任务的翻译结果
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+ * - `out_result`: 输出转写翻译结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_translate_transcribe_with_client(const struct DianyaClient *client,
+                                                    const char *task_id,
+                                                    const char *target_lang,
+                                                    struct FfiTranscribeTranslator *out_result,
+                                                    struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄获取转写分享链接
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `expiration_day`: 过期天数（0 表示使用默认值 7 天）
+ * - `out_link`: 输出分享链接结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_get_share_link_with_client(const struct DianyaClient *client,
+                                              const char *task_id,
+                                              int32_t expiration_day,
+                                              struct FfiShareLink *out_link,
+                                              struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄创建总结任务
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `utterances`: Utterance 数组指针
+ * - `utterances_len`: Utterance 数组长度
+ * - `out_summary`: 输出总结任务信息结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_create_summary_with_client(const struct DianyaClient *client,
+                                              const struct FfiUtterance *utterances,
+                                              uintptr_t utterances_len,
+                                              struct FfiSummaryCreator *out_summary,
+                                              struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄处理转写任务状态回调（服务器端使用）
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `request`: 回调请求结构体指针
+ * - `out_response`: 输出回调响应结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_callback_with_client(const struct DianyaClient *client,
+                                        const struct FfiCallbackRequest *request,
+                                        struct FfiCallbackResponse *out_response,
+                                        struct FfiError *out_error);
+
+/**
+ * 异步上传音频文件进行转写
+ *
+ * 立即返回，上传完成后在 runtime 线程上调用 `callback`
+ *
+ * # 参数
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `token`: Bearer token（C 字符串）
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示操作已启动）
+ */
+int transcribe_ffi_upload_async(const char *filepath,
+                                bool transcribe_only,
+                                bool short_asr,
+                                const char *model,
+                                const char *token,
+                                FfiUploadCallback callback,
+                                void *user_data,
+                                uint64_t *out_op_id,
+                                struct FfiError *out_error);
 
 /**
- * 获取转写分享链接
+ * 启动可取消的上传操作
+ *
+ * 立即返回操作句柄，可通过 `transcribe_ffi_op_wait` / `transcribe_ffi_op_poll` 查询进度，
+ * 通过 `transcribe_ffi_op_cancel` 取消，结束后用 `transcribe_ffi_op_upload_result` 获取结果。
+ * 句柄需调用 `transcribe_ffi_op_free` 释放。
  *
  * # 参数
- * - `task_id`: 任务ID（C 字符串）
- * - `expiration_day`: 过期天数（0 表示使用默认值 7 天）
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
  * - `token`: Bearer token（C 字符串）
- * - `out_link`: 输出分享链接结构体指针
+ * - `out_op`: 输出操作句柄指针
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_get_share_link(const char *task_id,
-                                  int32_t expiration_day,
-                                  const char *token,
-                                  struct FfiShareLink *out_link,
-                                  struct FfiError *out_error);
+int transcribe_ffi_upload_start(const char *filepath,
+                                bool transcribe_only,
+                                bool short_asr,
+                                const char *model,
+                                const char *token,
+                                struct FfiOperation **out_op,
+                                struct FfiError *out_error);
 
 /**
- * 获取转写任务状态
+ * 异步获取转写任务状态
+ *
+ * 立即返回，请求完成后在 runtime 线程上调用 `callback`
  *
  * # 参数
  * - `task_id`: 任务ID（可为 NULL，如果提供 share_id）
  * - `share_id`: 分享链接ID（可为 NULL，如果提供 task_id）
  * - `token`: Bearer token（C 字符串）
- * - `out_status`: 输出状态结构体指针
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_get_status(const char *task_id,
-                              const char *share_id,
-                              const char *token,
-                              struct FfiTranscribeStatus *out_status,
-                              struct FfiError *out_error);
+int transcribe_ffi_get_status_async(const char *task_id,
+                                    const char *share_id,
+                                    const char *token,
+                                    FfiStatusCallback callback,
+                                    void *user_data,
+                                    uint64_t *out_op_id,
+                                    struct FfiError *out_error);
 
 /**
- * 创建总结任务
+ * 异步导出转写内容或总结内容
+ *
+ * 立即返回，导出完成后在 runtime 线程上调用 `callback`
  *
  * # 参数
- * - `utterances`: Utterance 数组指针
- * - `utterances_len`: Utterance 数组长度
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
  * - `token`: Bearer token（C 字符串）
- * - `out_summary`: 输出总结任务信息结构体指针
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_create_summary(const struct FfiUtterance *utterances,
-                                  uintptr_t utterances_len,
-                                  const char *token,
-                                  struct FfiSummaryCreator *out_summary,
-                                  struct FfiError *out_error);
+int transcribe_ffi_export_async(const char *task_id,
+                                const char *export_type,
+                                const char *export_format,
+                                const char *token,
+                                FfiExportCallback callback,
+                                void *user_data,
+                                uint64_t *out_op_id,
+                                struct FfiError *out_error);
 
 /**
- * 上传音频文件进行转写
+ * 启动可取消的导出操作
+ *
+ * 立即返回操作句柄，结束后用 `transcribe_ffi_op_export_result` 获取数据。
+ * 句柄需调用 `transcribe_ffi_op_free` 释放。
  *
  * # 参数
- * - `filepath`: 音频文件路径（C 字符串）
- * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
- * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
- * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `task_id`: 任务ID（C 字符串）
+ * - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+ * - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
  * - `token`: Bearer token（C 字符串）
- * - `out_result`: 输出上传结果结构体指针
+ * - `out_op`: 输出操作句柄指针
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_upload(const char *filepath,
-                          bool transcribe_only,
-                          bool short_asr,
-                          const char *model,
-                          const char *token,
-                          struct FfiUploadResponse *out_result,
-                          struct FfiError *out_error);
+int transcribe_ffi_export_start(const char *task_id,
+                                const char *export_type,
+                                const char *export_format,
+                                const char *token,
+                                struct FfiOperation **out_op,
+                                struct FfiError *out_error);
 
 /**
- * 翻译文本
+ * 异步翻译文本
+ *
+ * 立即返回，翻译完成后在 runtime 线程上调用 `callback`
  *
  * # 参数
  * - `text`: 要翻译的文本（C 字符串）
  * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
  * - `token`: Bearer token（C 字符串）
- * - `out_result`: 输出文本翻译结果结构体指针
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_translate_text(const char *text,
-                                  const char *target_lang,
-                                  const char *token,
-                                  struct FfiTextTranslator *out_result,
-                                  struct FfiError *out_error);
+int transcribe_ffi_translate_text_async(const char *text,
+                                        const char *target_lang,
+                                        const char *token,
+                                        FfiTextTranslatorCallback callback,
+                                        void *user_data,
+                                        uint64_t *out_op_id,
+                                        struct FfiError *out_error);
 
 /**
- * 翻译 utterances 列表
+ * 异步翻译 utterances 列表
+ *
+ * 立即返回，翻译完成后在 runtime 线程上调用 `callback`。
+ * `utterances` 在函数返回前已被复制，调用方可立即释放。
  *
  * # 参数
  * - `utterances`: Utterance 数组指针
  * - `utterances_len`: Utterance 数组长度
  * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
  * - `token`: Bearer token（C 字符串）
- * - `out_result`: 输出 utterance 翻译结果结构体指针
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_translate_utterance(const struct FfiUtterance *utterances,
-                                       uintptr_t utterances_len,
-                                       const char *target_lang,
-                                       const char *token,
-                                       struct FfiUtteranceTranslator *out_result,
-                                       struct FfiError *out_error);
+int transcribe_ffi_translate_utterance_async(const struct FfiUtterance *utterances,
+                                             uintptr_t utterances_len,
+                                             const char *target_lang,
+                                             const char *token,
+                                             FfiUtteranceTranslatorCallback callback,
+                                             void *user_data,
+                                             uint64_t *out_op_id,
+                                             struct FfiError *out_error);
 
 /**
- * 获取转写任务的翻译结果
+ * 异步获取转写任务的翻译结果
+ *
+ * 立即返回，请求完成后在 runtime 线程上调用 `callback`
  *
  * # 参数
  * - `task_id`: 任务ID（C 字符串）
  * - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
  * - `token`: Bearer token（C 字符串）
- * - `out_result`: 输出转写翻译结果结构体指针
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_translate_transcribe(const char *task_id,
-                                        const char *target_lang,
+int transcribe_ffi_translate_transcribe_async(const char *task_id,
+                                              const char *target_lang,
+                                              const char *token,
+                                              FfiTranscribeTranslatorCallback callback,
+                                              void *user_data,
+                                              uint64_t *out_op_id,
+                                              struct FfiError *out_error);
+
+/**
+ * 异步创建实时转写会话
+ *
+ * 立即返回，会话创建完成后在 runtime 线程上调用 `callback`
+ *
+ * # 参数
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `token`: Bearer token（C 字符串）
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示操作已启动）
+ */
+int transcribe_ffi_create_session_async(const char *model,
                                         const char *token,
-                                        struct FfiTranscribeTranslator *out_result,
+                                        FfiSessionCreatorCallback callback,
+                                        void *user_data,
+                                        uint64_t *out_op_id,
                                         struct FfiError *out_error);
 
 /**
- * 处理转写任务状态回调（服务器端使用）
+ * 异步关闭实时转写会话
+ *
+ * 立即返回，会话关闭后在 runtime 线程上调用 `callback`
  *
  * # 参数
- * - `request`: 回调请求结构体指针
+ * - `task_id`: 任务ID（C 字符串）
  * - `token`: Bearer token（C 字符串）
- * - `out_response`: 输出回调响应结构体指针
+ * - `timeout`: 超时时间（秒），0 表示使用默认值 30 秒
+ * - `callback`: 完成回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_op_id`: 输出操作ID
  * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示操作已启动）
  */
-int transcribe_ffi_callback(const struct FfiCallbackRequest *request,
-                            const char *token,
-                            struct FfiCallbackResponse *out_response,
-                            struct FfiError *out_error);
+int transcribe_ffi_close_session_async(const char *task_id,
+                                       const char *token,
+                                       uint64_t timeout,
+                                       FfiSessionEnderCallback callback,
+                                       void *user_data,
+                                       uint64_t *out_op_id,
+                                       struct FfiError *out_error);
 
 /**
  * 创建实时转写会话
@@ -427,6 +2171,40 @@ int transcribe_ffi_close_session(const char *task_id,
                                  struct FfiSessionEnder *out_result,
                                  struct FfiError *out_error);
 
+/**
+ * 使用客户端句柄创建实时转写会话
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `out_session`: 输出的会话信息结构体指针
+ *
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_create_session_with_client(const struct DianyaClient *client,
+                                              const char *model,
+                                              struct FfiSessionCreator *out_session,
+                                              struct FfiError *out_error);
+
+/**
+ * 使用客户端句柄关闭实时转写会话
+ *
+ * # 参数
+ * - `client`: 客户端句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `timeout`: 超时时间（秒），0 表示使用默认值 30 秒
+ * - `out_result`: 输出的会话关闭结果结构体指针
+ *
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_close_session_with_client(const struct DianyaClient *client,
+                                             const char *task_id,
+                                             uint64_t timeout,
+                                             struct FfiSessionEnder *out_result,
+                                             struct FfiError *out_error);
+
 /**
  * 创建 WebSocket 连接句柄
  *
@@ -495,6 +2273,9 @@ int transcribe_ffi_ws_stop(struct TranscribeStream *handle, struct FfiError *out
 /**
  * 接收 WebSocket 消息（轮询方式）
  *
+ * 缓冲区不足时返回 `BufferTooSmall`，所需大小（含结尾的 NUL）写入 `message_len`，
+ * 消息保留在句柄中，下次调用时优先返回。
+ *
  * # 参数
  * - `handle`: WebSocket 句柄
  * - `message_json`: 输出消息 JSON 的缓冲区指针
@@ -502,7 +2283,7 @@ int transcribe_ffi_ws_stop(struct TranscribeStream *handle, struct FfiError *out
  * - `timeout_ms`: 超时时间（毫秒），0 表示立即返回
  *
  * # 返回
- * 错误码（0 表示成功）
+ * 错误码（0 表示成功，超时时 `message_len` 为 0；消息流已结束时为 `StreamClosed`）
  */
 int transcribe_ffi_ws_receive(struct TranscribeStream *handle,
                               char *message_json,
@@ -510,6 +2291,24 @@ int transcribe_ffi_ws_receive(struct TranscribeStream *handle,
                               uint64_t timeout_ms,
                               struct FfiError *out_error);
 
+/**
+ * 释放 WebSocket 连接内存
+ *
+ * # 参数
+ * - `handle`: WebSocket 句柄指针
+ */
+void transcribe_ffi_ws_free(struct TranscribeStream *handle);
+
+/**
+ * 释放本库分配的 C 字符串
+ *
+ * # 参数
+ * - `s`: C 字符串指针
+ */
+void transcribe_ffi_free_string(char *s);
+
+void transcribe_ffi_free_bytes(struct FfiBytes *b);
+
 void transcribe_ffi_free_share_link(struct FfiShareLink *s);
 
 void transcribe_ffi_free_transcribe_status(struct FfiTranscribeStatus *s);
@@ -529,3 +2328,196 @@ void transcribe_ffi_free_upload_response(struct FfiUploadResponse *s);
 void transcribe_ffi_free_session_creator(struct FfiSessionCreator *s);
 
 void transcribe_ffi_free_session_ender(struct FfiSessionEnder *s);
+
+/**
+ * 上传内存中的音频数据进行转写
+ *
+ * SDK 只支持从文件上传，数据会先写入临时文件，上传结束后删除。
+ *
+ * # 参数
+ * - `data`: 音频数据
+ * - `len`: 数据长度
+ * - `filename_hint`: 文件名（C 字符串，可为 null），服务器据扩展名识别格式
+ * - `mime`: MIME 类型（C 字符串，可为 null），文件名没有扩展名时用于推断扩展名
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_upload_bytes(const uint8_t *data,
+                                uintptr_t len,
+                                const char *filename_hint,
+                                const char *mime,
+                                bool transcribe_only,
+                                bool short_asr,
+                                const char *model,
+                                const char *token,
+                                struct FfiUploadResponse *out_result,
+                                struct FfiError *out_error);
+
+/**
+ * 通过读取回调上传音频数据进行转写
+ *
+ * 适用于无法一次载入内存的大文件。数据在当前线程上分块读取并写入临时文件，
+ * 读取结束后上传，上传结束后删除临时文件。
+ *
+ * # 参数
+ * - `read_cb`: 读取回调
+ * - `user_data`: 原样传给回调的用户数据
+ * - `filename_hint`: 文件名（C 字符串，可为 null），服务器据扩展名识别格式
+ * - `mime`: MIME 类型（C 字符串，可为 null），文件名没有扩展名时用于推断扩展名
+ * - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+ * - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+ * - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_upload_reader(FfiReadCallback read_cb,
+                                 void *user_data,
+                                 const char *filename_hint,
+                                 const char *mime,
+                                 bool transcribe_only,
+                                 bool short_asr,
+                                 const char *model,
+                                 const char *token,
+                                 struct FfiUploadResponse *out_result,
+                                 struct FfiError *out_error);
+
+/**
+ * 初始化上传选项
+ *
+ * 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+ *
+ * # 参数
+ * - `options`: 上传选项结构体指针
+ */
+void transcribe_ffi_upload_options_init(struct FfiUploadOptions *options);
+
+/**
+ * 按上传选项上传音频文件进行转写
+ *
+ * # 参数
+ * - `filepath`: 音频文件路径（C 字符串）
+ * - `options`: 上传选项
+ * - `token`: Bearer token（C 字符串）
+ * - `out_result`: 输出上传结果结构体指针
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_upload_with_options(const char *filepath,
+                                       const struct FfiUploadOptions *options,
+                                       const char *token,
+                                       struct FfiUploadResponse *out_result,
+                                       struct FfiError *out_error);
+
+/**
+ * 创建任务监视器
+ *
+ * 监视器在后台轮询已添加的任务：状态未变化时按 `poll_config` 逐步拉长间隔，
+ * 所有任务共享每秒最大请求数的限制。状态变化、完成和失败时产生事件；
+ * 提供回调时在 runtime 工作线程上调用回调，否则放入事件队列，
+ * 由 `transcribe_ffi_watcher_next_event` 取出。队列中积压约 1024 个事件时暂停轮询，
+ * 直到宿主取出事件，因此不取出事件不会无限占用内存。
+ * 监视器不计入 `transcribe_ffi_runtime_shutdown` 等待的操作，runtime 关闭后停止工作。
+ *
+ * # 参数
+ * - `token`: Bearer token（C 字符串）
+ * - `poll_config`: 轮询配置，为 null 时使用默认配置；`timeout_ms` 为每个任务从添加起的超时时间
+ * - `max_requests_per_sec`: 每秒最大状态请求数，0 表示使用默认值 10
+ * - `callback`: 事件回调，为 null 时使用事件队列
+ * - `user_data`: 原样传给回调的用户数据
+ * - `out_watcher`: 输出监视器句柄指针，需调用 `transcribe_ffi_watcher_free` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_watcher_create(const char *token,
+                                  const struct FfiPollConfig *poll_config,
+                                  uint32_t max_requests_per_sec,
+                                  FfiWatchCallback callback,
+                                  void *user_data,
+                                  struct TaskWatcher **out_watcher,
+                                  struct FfiError *out_error);
+
+/**
+ * 添加要监视的任务
+ *
+ * 任务在下一个限流时隙被首次查询。已在监视中的任务不会重复添加。
+ *
+ * # 参数
+ * - `watcher`: 监视器句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_watcher_add(const struct TaskWatcher *watcher,
+                               const char *task_id,
+                               struct FfiError *out_error);
+
+/**
+ * 停止监视任务
+ *
+ * 正在进行的查询结果会被丢弃，不再产生该任务的新事件（已在队列中的事件仍可取出）。
+ * 任务不在监视中时无效果。
+ *
+ * # 参数
+ * - `watcher`: 监视器句柄
+ * - `task_id`: 任务ID（C 字符串）
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功）
+ */
+int transcribe_ffi_watcher_remove(const struct TaskWatcher *watcher,
+                                  const char *task_id,
+                                  struct FfiError *out_error);
+
+/**
+ * 获取正在监视的任务数
+ *
+ * # 参数
+ * - `watcher`: 监视器句柄
+ *
+ * # 返回
+ * 任务数；句柄为 null 时返回 0
+ */
+uintptr_t transcribe_ffi_watcher_count(const struct TaskWatcher *watcher);
+
+/**
+ * 从事件队列取出下一个事件
+ *
+ * 仅适用于创建时未提供回调的监视器。
+ *
+ * # 参数
+ * - `watcher`: 监视器句柄
+ * - `timeout_ms`: 队列为空时的等待时间（毫秒），0 表示立即返回
+ * - `out_event`: 输出事件结构体指针，需调用 `transcribe_ffi_free_watch_event` 释放
+ * - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+ * # 返回
+ * 错误码（0 表示成功，超时时 `task_id` 为 null；runtime 已关闭时为 `Cancelled`）
+ */
+int transcribe_ffi_watcher_next_event(const struct TaskWatcher *watcher,
+                                      uint64_t timeout_ms,
+                                      struct FfiWatchEvent *out_event,
+                                      struct FfiError *out_error);
+
+void transcribe_ffi_free_watch_event(struct FfiWatchEvent *e);
+
+/**
+ * 释放监视器句柄，停止轮询并丢弃未取出的事件
+ *
+ * 阻塞直到后台任务结束（包括等待正在执行的回调返回），返回后不会再调用回调。
+ * 不能在回调中调用；在 runtime 线程上调用时不等待并记录警告。
+ *
+ * # 参数
+ * - `watcher`: 监视器句柄
+ */
+void transcribe_ffi_watcher_free(struct TaskWatcher *watcher);
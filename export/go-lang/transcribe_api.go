@@ -37,6 +37,15 @@ const (
 	ErrorInvalidAPI   ErrorCode = ErrorCode(C.InvalidApiKey)
 	ErrorJsonError    ErrorCode = ErrorCode(C.JsonError)
 	ErrorOtherError   ErrorCode = ErrorCode(C.OtherError)
+	ErrorCancelled    ErrorCode = ErrorCode(C.Cancelled)
+	ErrorBufferSmall  ErrorCode = ErrorCode(C.BufferTooSmall)
+	ErrorTimeout      ErrorCode = ErrorCode(C.Timeout)
+	ErrorNotFound     ErrorCode = ErrorCode(C.NotFound)
+	ErrorRateLimited  ErrorCode = ErrorCode(C.RateLimited)
+	ErrorUnauthorized ErrorCode = ErrorCode(C.Unauthorized)
+	ErrorStreamClosed ErrorCode = ErrorCode(C.StreamClosed)
+	ErrorPanic        ErrorCode = ErrorCode(C.Panic)
+	ErrorRuntimeDown  ErrorCode = ErrorCode(C.RuntimeShutDown)
 )
 
 // UploadResponse 上传响应
@@ -137,10 +146,13 @@ func ffiError(code C.int, err *C.FfiError, context string) error {
 	var msg string
 	if err != nil && err.message != nil {
 		msg = C.GoString(err.message)
-		C.transcribe_ffi_free_error(err)
 	} else {
 		msg = fmt.Sprintf("%s failed with error code: %d", context, int(code))
 	}
+	if err != nil {
+		// 错误详情在 message 为 null 时也可能已分配
+		C.transcribe_ffi_free_error(err)
+	}
 	return fmt.Errorf(msg)
 }
 
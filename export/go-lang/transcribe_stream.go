@@ -11,11 +11,15 @@ package dianyaapi
 import "C"
 
 import (
+	"errors"
 	"fmt"
 	"time"
 	"unsafe"
 )
 
+// ErrStreamClosed 表示 WebSocket 消息流已结束，之后不会再收到消息
+var ErrStreamClosed = errors.New("stream closed")
+
 // CreateSession 创建实时转写会话
 func CreateSession(model, token string) (*SessionResponse, error) {
 	cModel := C.CString(model)
@@ -184,15 +188,12 @@ func (s *Stream) SendBytes(data []byte) error {
 }
 
 // Receive 读取一条消息
-// 返回值：(message, ok, error) 。ok=false 表示超时或无消息。
+// 返回值：(message, ok, error) 。ok=false 表示超时或无消息；消息流结束时返回 ErrStreamClosed。
 func (s *Stream) Receive(timeout time.Duration) (string, bool, error) {
 	if s == nil || s.handle == nil {
 		return "", false, fmt.Errorf("stream handle is nil")
 	}
 
-	buffer := make([]byte, 64*1024)
-	length := C.size_t(len(buffer))
-
 	var timeoutMS C.uint64_t
 	if timeout > 0 {
 		timeoutMS = C.uint64_t(timeout / time.Millisecond)
@@ -200,23 +201,37 @@ func (s *Stream) Receive(timeout time.Duration) (string, bool, error) {
 		timeoutMS = 0
 	}
 
-	var cError C.FfiError
-	code := C.transcribe_ffi_ws_receive(
-		s.handle,
-		(*C.char)(unsafe.Pointer(&buffer[0])),
-		(*C.size_t)(unsafe.Pointer(&length)),
-		timeoutMS,
-		&cError,
-	)
-	if err := ffiError(code, &cError, "ws_receive"); err != nil {
-		return "", false, err
-	}
+	buffer := make([]byte, 64*1024)
+	for {
+		length := C.size_t(len(buffer))
+		var cError C.FfiError
+		code := C.transcribe_ffi_ws_receive(
+			s.handle,
+			(*C.char)(unsafe.Pointer(&buffer[0])),
+			(*C.size_t)(unsafe.Pointer(&length)),
+			timeoutMS,
+			&cError,
+		)
+		switch ErrorCode(code) {
+		case ErrorBufferSmall:
+			// 消息保留在句柄中，用所需大小的缓冲区重新读取
+			C.transcribe_ffi_free_error(&cError)
+			buffer = make([]byte, int(length))
+			continue
+		case ErrorStreamClosed:
+			C.transcribe_ffi_free_error(&cError)
+			return "", false, ErrStreamClosed
+		}
+		if err := ffiError(code, &cError, "ws_receive"); err != nil {
+			return "", false, err
+		}
 
-	if length == 0 {
-		return "", false, nil
+		if length == 0 {
+			return "", false, nil
+		}
+		msg := C.GoStringN((*C.char)(unsafe.Pointer(&buffer[0])), C.int(length))
+		return msg, true, nil
 	}
-	msg := C.GoStringN((*C.char)(unsafe.Pointer(&buffer[0])), C.int(length))
-	return msg, true, nil
 }
 
 // Close 释放 WebSocket 资源
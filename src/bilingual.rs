@@ -0,0 +1,427 @@
+use std::ffi::*;
+use std::fmt::Write;
+
+use common::Error;
+use transcribe::types::Utterance;
+
+use crate::{
+    error::FfiError,
+    subtitle::{
+        check_utterance, render, speaker_label, subtitle_format_from_str, utterances_from_raw,
+        SubtitleLine, SubtitleOptions,
+    },
+    utils::*,
+    FfiTranscribeTranslator, FfiUtterance, FfiUtteranceTranslator,
+};
+
+/// 双语渲染格式
+enum BilingualFormat {
+    /// SRT / WebVTT 字幕，每条字幕包含原文行和译文行
+    Subtitle(SubtitleOptions),
+    /// 原文与译文并列的 Markdown 表格
+    Markdown,
+    /// 纯文本，原文与译文逐段交替
+    Text,
+}
+
+/// 双语渲染选项，所有字段为 0 / null 时与默认选项相同
+///
+/// 调用方须先用 `transcribe_ffi_bilingual_options_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiBilingualOptions)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiBilingualOptions {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 输出格式（C 字符串）："srt"、"vtt"、"markdown" 或 "text"，为 null 时使用 "srt"
+    pub format: *const c_char,
+    /// 说话人标签（C 字符串，可为 null），其中的 "{}" 替换为说话人编号，例如 "Speaker {}"；
+    /// 为 null 时不标注说话人
+    pub speaker_label: *const c_char,
+    /// 译文是否在原文之前（字幕中的行序，表格中的列序）
+    pub translation_first: bool,
+    /// Markdown 和纯文本中是否包含开始时间
+    pub timestamps: bool,
+    /// Markdown 表格原文列标题（C 字符串，可为 null，默认 "Original"）
+    pub original_header: *const c_char,
+    /// Markdown 表格译文列标题（C 字符串，可为 null，默认 "Translation"）
+    pub translation_header: *const c_char,
+    /// 字幕每行最大字符数，超出时折行，0 表示不折行
+    pub max_line_length: usize,
+    /// 字幕的最短显示时间（毫秒）
+    pub min_duration_ms: u64,
+    /// 所有时间的偏移（毫秒），可为负
+    pub offset_ms: i64,
+}
+
+/// 解析后的双语渲染选项
+struct BilingualOptions {
+    format: BilingualFormat,
+    speaker_label: Option<String>,
+    translation_first: bool,
+    timestamps: bool,
+    original_header: String,
+    translation_header: String,
+    /// 时间偏移（秒）
+    offset: f64,
+}
+
+/// 读取可为 null 的 C 字符串，为 null 或空时使用默认值
+fn str_or(s: *const c_char, default: &str) -> Result<String, Error> {
+    if s.is_null() {
+        return Ok(default.to_string());
+    }
+    parse_c_str(
+        s,
+        |s| Ok(if s.is_empty() { default } else { s }.to_string()),
+    )
+}
+
+impl TryFrom<&FfiBilingualOptions> for BilingualOptions {
+    type Error = Error;
+
+    fn try_from(o: &FfiBilingualOptions) -> Result<Self, Self::Error> {
+        let format = str_or(o.format, "srt")?;
+        let speaker_label = Some(str_or(o.speaker_label, "")?).filter(|s| !s.is_empty());
+        let offset = o.offset_ms as f64 / 1000.0;
+
+        let format = match format.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => BilingualFormat::Markdown,
+            "text" | "txt" => BilingualFormat::Text,
+            "srt" | "vtt" | "webvtt" => BilingualFormat::Subtitle(SubtitleOptions {
+                format: subtitle_format_from_str(&format)?,
+                speaker_label: speaker_label.clone(),
+                max_line_length: o.max_line_length,
+                max_lines: 0,
+                min_duration: o.min_duration_ms as f64 / 1000.0,
+                offset,
+                translation_first: o.translation_first,
+            }),
+            _ => return Err(Error::InvalidInput("Invalid bilingual format".to_string())),
+        };
+
+        Ok(Self {
+            format,
+            speaker_label,
+            translation_first: o.translation_first,
+            timestamps: o.timestamps,
+            original_header: str_or(o.original_header, "Original")?,
+            translation_header: str_or(o.translation_header, "Translation")?,
+            offset,
+        })
+    }
+}
+
+impl Default for BilingualOptions {
+    fn default() -> Self {
+        Self {
+            format: BilingualFormat::Subtitle(SubtitleOptions::default()),
+            speaker_label: None,
+            translation_first: false,
+            timestamps: false,
+            original_header: "Original".to_string(),
+            translation_header: "Translation".to_string(),
+            offset: 0.0,
+        }
+    }
+}
+
+impl BilingualOptions {
+    /// 从 C 指针读取选项，为 null 时使用默认选项
+    fn from_ptr(options: *const FfiBilingualOptions) -> Result<Self, Error> {
+        match unsafe { read_sized_struct(options, 0)? } {
+            Some(options) => BilingualOptions::try_from(&options),
+            None => Ok(BilingualOptions::default()),
+        }
+    }
+
+    fn label(&self, speaker: i32) -> Option<String> {
+        speaker_label(self.speaker_label.as_deref(), speaker)
+    }
+
+    /// 按布局排列原文和译文
+    fn order<'a>(&self, original: &'a str, translation: &'a str) -> (&'a str, &'a str) {
+        if self.translation_first {
+            (translation, original)
+        } else {
+            (original, translation)
+        }
+    }
+}
+
+/// 格式化为 HH:MM:SS
+fn clock(seconds: f64) -> String {
+    let s = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+/// 转义 Markdown 表格单元格
+fn escape_cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn render_markdown(items: &[SubtitleLine], options: &BilingualOptions) -> String {
+    let mut columns = Vec::new();
+    if options.timestamps {
+        columns.push("Time".to_string());
+    }
+    if options.speaker_label.is_some() {
+        columns.push("Speaker".to_string());
+    }
+    let (first, second) = options.order(&options.original_header, &options.translation_header);
+    columns.push(escape_cell(first));
+    columns.push(escape_cell(second));
+
+    let mut out = String::new();
+    let _ = writeln!(out, "| {} |", columns.join(" | "));
+    let _ = writeln!(out, "|{}", " --- |".repeat(columns.len()));
+    for item in items {
+        let u = &item.utterance;
+        let mut cells = Vec::new();
+        if options.timestamps {
+            cells.push(clock(u.start_time + options.offset));
+        }
+        if options.speaker_label.is_some() {
+            cells.push(escape_cell(&options.label(u.speaker).unwrap_or_default()));
+        }
+        let (first, second) =
+            options.order(&u.text, item.translation.as_deref().unwrap_or_default());
+        cells.push(escape_cell(first));
+        cells.push(escape_cell(second));
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+    out
+}
+
+fn render_text(items: &[SubtitleLine], options: &BilingualOptions) -> String {
+    let mut out = String::new();
+    for item in items {
+        let u = &item.utterance;
+        if options.timestamps {
+            let _ = write!(out, "[{}] ", clock(u.start_time + options.offset));
+        }
+        if let Some(label) = options.label(u.speaker) {
+            let _ = write!(out, "{}: ", label);
+        }
+        let (first, second) =
+            options.order(&u.text, item.translation.as_deref().unwrap_or_default());
+        let _ = writeln!(out, "{}", first.trim());
+        if !second.trim().is_empty() {
+            let _ = writeln!(out, "{}", second.trim());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// 渲染双语结果并写入输出参数
+fn write_bilingual(
+    items: &[SubtitleLine],
+    options: &BilingualOptions,
+    out_text: *mut *mut c_char,
+) -> Result<(), Error> {
+    let text = match &options.format {
+        BilingualFormat::Subtitle(subtitle) => render(items, subtitle),
+        BilingualFormat::Markdown => render_markdown(items, options),
+        BilingualFormat::Text => render_text(items, options),
+    };
+    let text = CString::new(text).map_err(|e| Error::OtherError(e.to_string()))?;
+    unsafe {
+        *out_text = text.into_raw();
+    }
+    Ok(())
+}
+
+/// 读取可为 null 的译文
+fn translation_from_raw(s: *const c_char) -> Option<String> {
+    (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) }.to_string_lossy().to_string())
+}
+
+/// 将转写翻译结果渲染为双语字幕、Markdown 表格或纯文本
+///
+/// 在本地渲染，不请求服务端，时间单位为秒。
+///
+/// # 参数
+/// - `translator`: `transcribe_ffi_translate_transcribe` 返回的翻译结果
+/// - `options`: 渲染选项，为 null 时使用默认选项（SRT，原文在前，不标注说话人）
+/// - `out_text`: 输出文本指针，需调用 `transcribe_ffi_free_string` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_render_transcribe_translation(
+    translator: *const FfiTranscribeTranslator,
+    options: *const FfiBilingualOptions,
+    out_text: *mut *mut c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_text.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+        let translator = unsafe { translator.as_ref() }
+            .ok_or_else(|| Error::InvalidInput("Invalid translator".to_string()))?;
+        let options = BilingualOptions::from_ptr(options)?;
+
+        let details = if translator.details.is_null() || translator.details_len == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(translator.details, translator.details_len) }
+        };
+        details
+            .iter()
+            .try_for_each(|d| check_utterance(&d.utterance))?;
+        let items: Vec<SubtitleLine> = details
+            .iter()
+            .map(|d| SubtitleLine {
+                utterance: Utterance::from(&d.utterance),
+                translation: translation_from_raw(d.translation),
+            })
+            .collect();
+
+        write_bilingual(&items, &options, out_text)
+    })
+}
+
+/// 将 utterance 翻译结果与原文一起渲染为双语字幕、Markdown 表格或纯文本
+///
+/// `utterances` 为调用 `transcribe_ffi_translate_utterance` 时传入的原文，
+/// 与翻译结果按顺序一一对应，时间取自原文。
+///
+/// # 参数
+/// - `utterances`: 原文 utterance 数组指针
+/// - `utterances_len`: 原文 utterance 数量，需与翻译结果数量一致
+/// - `translator`: `transcribe_ffi_translate_utterance` 返回的翻译结果
+/// - `options`: 渲染选项，为 null 时使用默认选项（SRT，原文在前，不标注说话人）
+/// - `out_text`: 输出文本指针，需调用 `transcribe_ffi_free_string` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_render_utterance_translation(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    translator: *const FfiUtteranceTranslator,
+    options: *const FfiBilingualOptions,
+    out_text: *mut *mut c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_text.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+        let translator = unsafe { translator.as_ref() }
+            .ok_or_else(|| Error::InvalidInput("Invalid translator".to_string()))?;
+        let options = BilingualOptions::from_ptr(options)?;
+
+        let originals = utterances_from_raw(utterances, utterances_len)?;
+        let translations = utterances_from_raw(translator.details, translator.details_len)?;
+        if originals.len() != translations.len() {
+            return Err(Error::InvalidInput(format!(
+                "Expected {} translated utterances, got {}",
+                originals.len(),
+                translations.len()
+            )));
+        }
+        let items: Vec<SubtitleLine> = originals
+            .into_iter()
+            .zip(translations)
+            .map(|(utterance, translation)| SubtitleLine {
+                utterance,
+                translation: Some(translation.text),
+            })
+            .collect();
+
+        write_bilingual(&items, &options, out_text)
+    })
+}
+
+/// 初始化双语渲染选项
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `options`: 双语渲染选项结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_bilingual_options_init(options: *mut FfiBilingualOptions) {
+    if options.is_null() {
+        return;
+    }
+
+    unsafe {
+        *options = FfiBilingualOptions {
+            struct_size: std::mem::size_of::<FfiBilingualOptions>(),
+            format: std::ptr::null(),
+            speaker_label: std::ptr::null(),
+            translation_first: false,
+            timestamps: false,
+            original_header: std::ptr::null(),
+            translation_header: std::ptr::null(),
+            max_line_length: 0,
+            min_duration_ms: 0,
+            offset_ms: 0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<SubtitleLine> {
+        [
+            (61.0, 0, "Hello | world", Some("你好 | 世界")),
+            (62.5, 1, "Bye", None),
+        ]
+        .into_iter()
+        .map(|(start, speaker, text, translation)| SubtitleLine {
+            utterance: Utterance {
+                start_time: start,
+                end_time: start + 1.0,
+                speaker,
+                text: text.to_string(),
+            },
+            translation: translation.map(str::to_string),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn renders_markdown_table() {
+        let options = BilingualOptions {
+            format: BilingualFormat::Markdown,
+            speaker_label: Some("S{}".to_string()),
+            translation_first: true,
+            timestamps: true,
+            ..BilingualOptions::default()
+        };
+        assert_eq!(
+            render_markdown(&items(), &options),
+            "| Time | Speaker | Translation | Original |\n\
+             | --- | --- | --- | --- |\n\
+             | 00:01:01 | S0 | 你好 \\| 世界 | Hello \\| world |\n\
+             | 00:01:02 | S1 |  | Bye |\n"
+        );
+    }
+
+    #[test]
+    fn renders_text() {
+        let options = BilingualOptions {
+            format: BilingualFormat::Text,
+            timestamps: true,
+            offset: -61.0,
+            ..BilingualOptions::default()
+        };
+        assert_eq!(
+            render_text(&items(), &options),
+            "[00:00:00] Hello | world\n你好 | 世界\n\n[00:00:01] Bye\n\n"
+        );
+    }
+
+    #[test]
+    fn renders_subtitle_with_translation_line() {
+        let text = render(&items(), &SubtitleOptions::default());
+        assert!(
+            text.starts_with("1\n00:01:01,000 --> 00:01:02,000\nHello | world\n你好 | 世界\n\n")
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::ffi::*;
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use common::Error;
+
+use crate::{
+    error::FfiError,
+    metrics,
+    retry::{with_retry, FfiRetryPolicy, RetryPolicy},
+    runtime::block_on,
+    token::{self, with_token_refresh},
+    utils::*,
+};
+
+/// 客户端配置快照
+#[derive(Clone)]
+pub(crate) struct ClientConfig {
+    pub token: String,
+    pub timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+}
+
+/// 客户端句柄，持有 token、默认超时和重试策略
+/// 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
+pub struct DianyaClient {
+    config: RwLock<ClientConfig>,
+}
+
+impl DianyaClient {
+    /// 从 C 指针获取客户端引用
+    pub(crate) unsafe fn from_ptr<'a>(client: *const DianyaClient) -> Result<&'a Self, Error> {
+        client
+            .as_ref()
+            .ok_or_else(|| Error::InvalidInput("Invalid client handle".to_string()))
+    }
+
+    /// 获取当前配置的快照
+    pub(crate) fn config(&self) -> Result<ClientConfig, FfiError> {
+        self.config
+            .read()
+            .map(|c| c.clone())
+            .map_err(FfiError::poisoned)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ClientConfig)) -> Result<(), FfiError> {
+        let mut guard = self.config.write().map_err(FfiError::poisoned)?;
+        f(&mut guard);
+        Ok(())
+    }
+
+    /// 使用客户端配置同步执行请求
+    ///
+    /// `f` 以 token 为参数构造请求 future，每次重试都会重新调用。
+    /// 每次尝试都会应用默认超时；仅 `idempotent` 为 true 的请求会按重试策略重试，
+    /// 但 token 失效时所有请求都会在刷新 token 后重试一次。
+    pub(crate) fn block_on<T, F, Fut>(&self, idempotent: bool, f: F) -> Result<T, FfiError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let config = self.config()?;
+        let policy = if idempotent {
+            config.retry.clone()
+        } else {
+            RetryPolicy::NONE
+        };
+
+        let result = block_on(with_retry(&policy, || async {
+            let fut = with_token_refresh(&config.token, &f);
+            match config.timeout {
+                Some(timeout) => match metrics::timeout(timeout, fut).await {
+                    Ok(result) => result.map_err(FfiError::from),
+                    Err(_) => {
+                        log::warn!("Request timed out after {} ms", timeout.as_millis());
+                        Err(FfiError::timeout(timeout))
+                    }
+                },
+                None => fut.await.map_err(FfiError::from),
+            }
+        }));
+
+        // token 已被刷新时保存新 token（期间未被宿主修改的情况下）
+        let token = token::current(&config.token);
+        if token != config.token {
+            self.update(|c| {
+                if c.token == config.token {
+                    c.token = token;
+                }
+            })?;
+        }
+
+        result
+    }
+}
+
+/// 创建客户端句柄
+///
+/// # 参数
+/// - `token`: Bearer token（C 字符串）
+/// - `out_client`: 输出客户端句柄指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_create(
+    token: *const c_char,
+    out_client: *mut *mut DianyaClient,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_client.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let client = Box::new(DianyaClient {
+            config: RwLock::new(ClientConfig {
+                token,
+                timeout: None,
+                retry: RetryPolicy::NONE,
+            }),
+        });
+
+        unsafe {
+            *out_client = Box::into_raw(client);
+        }
+
+        Ok(())
+    })
+}
+
+/// 更新客户端的 Bearer token
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `token`: Bearer token（C 字符串）
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_set_token(
+    client: *const DianyaClient,
+    token: *const c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        client.update(|c| c.token = token)
+    })
+}
+
+/// 设置客户端请求的默认超时时间
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `timeout_ms`: 超时时间（毫秒），0 表示不设超时
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_set_timeout(
+    client: *const DianyaClient,
+    timeout_ms: u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+        let timeout = if timeout_ms == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(timeout_ms))
+        };
+        client.update(|c| c.timeout = timeout)
+    })
+}
+
+/// 设置客户端的重试策略
+///
+/// 仅对幂等请求（获取状态、导出、获取翻译结果）生效，且只在网络错误、超时、限流和 5xx 错误时重试。
+/// 等同于以固定间隔调用 `transcribe_ffi_client_set_retry_config`。
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `max_retries`: 最大重试次数（0 表示不重试）
+/// - `retry_delay_ms`: 两次重试之间的间隔（毫秒）
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_set_retry_policy(
+    client: *const DianyaClient,
+    max_retries: u32,
+    retry_delay_ms: u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+        client.update(|c| {
+            c.retry = RetryPolicy::fixed(max_retries, Duration::from_millis(retry_delay_ms))
+        })
+    })
+}
+
+/// 设置客户端的重试策略（指数退避）
+///
+/// 仅对幂等请求（获取状态、导出、获取分享链接、获取转写翻译结果）生效，
+/// 上传、创建总结、文本与 utterance 翻译等请求不会重试。
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `policy`: 重试策略，为 null 时不重试
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_set_retry_config(
+    client: *const DianyaClient,
+    policy: *const FfiRetryPolicy,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+        let policy = RetryPolicy::from_ptr(policy)?;
+        client.update(|c| c.retry = policy)
+    })
+}
+
+/// 释放客户端句柄
+///
+/// # 参数
+/// - `client`: 客户端句柄
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_client_free(client: *mut DianyaClient) {
+    if client.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(client);
+    }
+}
@@ -1,8 +1,15 @@
+mod details;
+mod last_error;
+pub use details::*;
+pub use last_error::*;
+
 use common::Error;
 use std::ffi::{c_char, c_int, CString};
+use std::fmt::Display;
+use std::time::Duration;
 
 #[repr(C)]
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum ErrorCode {
     // Success = 0,
     WsError = 1,
@@ -14,9 +21,51 @@ pub enum ErrorCode {
     InvalidApiKey = 7,
     JsonError = 8,
     OtherError = 9,
+    Cancelled = 10,
+    /// 输出缓冲区不足，所需大小见错误详情中的 `required_size`
+    BufferTooSmall = 11,
+    Timeout = 12,
+    NotFound = 13,
+    RateLimited = 14,
+    Unauthorized = 15,
+    /// WebSocket 消息流已结束
+    StreamClosed = 16,
+    /// 本库内部发生 panic（包括因此被毒化的锁）
+    Panic = 17,
+    /// runtime 已通过 `transcribe_ffi_runtime_shutdown` 关闭
+    RuntimeShutDown = 18,
     UnknownError = -1,
 }
 
+impl TryFrom<c_int> for ErrorCode {
+    type Error = c_int;
+
+    fn try_from(code: c_int) -> Result<Self, Self::Error> {
+        Ok(match code {
+            1 => ErrorCode::WsError,
+            2 => ErrorCode::HttpError,
+            3 => ErrorCode::ServerError,
+            4 => ErrorCode::InvalidInput,
+            5 => ErrorCode::InvalidResponse,
+            6 => ErrorCode::InvalidToken,
+            7 => ErrorCode::InvalidApiKey,
+            8 => ErrorCode::JsonError,
+            9 => ErrorCode::OtherError,
+            10 => ErrorCode::Cancelled,
+            11 => ErrorCode::BufferTooSmall,
+            12 => ErrorCode::Timeout,
+            13 => ErrorCode::NotFound,
+            14 => ErrorCode::RateLimited,
+            15 => ErrorCode::Unauthorized,
+            16 => ErrorCode::StreamClosed,
+            17 => ErrorCode::Panic,
+            18 => ErrorCode::RuntimeShutDown,
+            -1 => ErrorCode::UnknownError,
+            _ => return Err(code),
+        })
+    }
+}
+
 /// FFI 错误信息结构体
 #[repr(C)]
 pub struct FfiError {
@@ -24,61 +73,171 @@ pub struct FfiError {
     pub code: ErrorCode,
     /// 错误信息（C 字符串，可能为 null）
     pub message: *mut c_char,
+    /// 结构化详情（可能为 null），通过 `transcribe_ffi_error_details` 读取
+    pub details: *mut ErrorDetails,
 }
 
+// 错误信息字符串和详情由 FfiError 独占，可以在线程间转移
+unsafe impl Send for FfiError {}
+
 impl FfiError {
     pub fn new(code: ErrorCode, message: *mut c_char) -> Self {
-        Self { code, message }
+        Self {
+            code,
+            message,
+            details: std::ptr::null_mut(),
+        }
+    }
+
+    /// 附加结构化详情，替换已有的详情
+    fn with_details(mut self, details: ErrorDetails) -> Self {
+        free_details(&mut self.details);
+        if !details.is_empty() {
+            self.details = Box::into_raw(Box::new(details));
+        }
+        self
+    }
+
+    /// 结构化详情
+    pub(crate) fn details(&self) -> Option<&ErrorDetails> {
+        unsafe { self.details.as_ref() }
+    }
+
+    /// 可修改的结构化详情，没有详情时先创建
+    pub(crate) fn details_mut(&mut self) -> &mut ErrorDetails {
+        if self.details.is_null() {
+            self.details = Box::into_raw(Box::default());
+        }
+        unsafe { &mut *self.details }
+    }
+
+    fn with_message(code: ErrorCode, message: impl Into<String>) -> Self {
+        let message = CString::new(message.into())
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut());
+        Self::new(code, message)
+    }
+
+    /// 操作已被取消
+    pub(crate) fn cancelled() -> Self {
+        Self::with_message(ErrorCode::Cancelled, "Operation cancelled")
+    }
+
+    /// 请求超时
+    pub(crate) fn timeout(after: Duration) -> Self {
+        Self::with_message(
+            ErrorCode::Timeout,
+            format!("Request timed out after {} ms", after.as_millis()),
+        )
+        .with_details(ErrorDetails {
+            retryable: true,
+            ..Default::default()
+        })
+    }
+
+    /// 输出缓冲区不足
+    pub(crate) fn buffer_too_small(required_size: usize) -> Self {
+        Self::with_message(
+            ErrorCode::BufferTooSmall,
+            format!("Buffer too small, need {} bytes", required_size),
+        )
+        .with_details(ErrorDetails {
+            required_size,
+            ..Default::default()
+        })
     }
 
-    /// 填充错误信息到输出参数
-    pub fn fill_error(out_error: *mut FfiError, err: Error) -> c_int {
+    /// WebSocket 消息流已结束
+    pub(crate) fn stream_closed() -> Self {
+        Self::with_message(ErrorCode::StreamClosed, "Stream closed")
+    }
+
+    /// runtime 已关闭
+    pub(crate) fn runtime_shut_down() -> Self {
+        Self::with_message(
+            ErrorCode::RuntimeShutDown,
+            "Runtime has been shut down, call transcribe_ffi_runtime_init to restart it",
+        )
+    }
+
+    /// 锁因之前的 panic 被毒化
+    pub(crate) fn poisoned(e: impl Display) -> Self {
+        Self::with_message(ErrorCode::Panic, format!("Failed to acquire lock: {}", e))
+    }
+
+    /// 捕获到 panic
+    pub(crate) fn panic(message: String) -> Self {
+        Self::with_message(ErrorCode::Panic, message)
+    }
+
+    /// 填充错误信息到输出参数，并记录为当前线程的最近错误
+    pub fn fill_error(out_error: *mut FfiError, err: impl Into<FfiError>) -> c_int {
+        let mut ffi_err: FfiError = err.into();
+        set_last_error(&ffi_err);
+        if log::log_enabled!(log::Level::Debug) && !ffi_err.message.is_null() {
+            let message = unsafe { std::ffi::CStr::from_ptr(ffi_err.message) };
+            log::debug!(
+                "Call failed with {:?}: {}",
+                ffi_err.code,
+                message.to_string_lossy()
+            );
+        }
+        let code = ffi_err.code as c_int;
         if !out_error.is_null() {
-            let ffi_err: FfiError = err.into();
             unsafe {
                 (*out_error).code = ffi_err.code;
                 (*out_error).message = ffi_err.message;
+                (*out_error).details = ffi_err.details;
             }
-            ffi_err.code as c_int
         } else {
-            ErrorCode::UnknownError as c_int
+            transcribe_ffi_free_error(&mut ffi_err);
         }
+        code
+    }
+}
+
+/// 错误码和错误信息
+fn describe(e: &Error) -> (ErrorCode, String) {
+    match e {
+        Error::WsError(err) => (ErrorCode::WsError, format!("Websocket Error: {}", err)),
+        Error::HttpError(err) => (ErrorCode::HttpError, format!("HTTP error: {}", err)),
+        Error::ServerError(msg) => (ErrorCode::ServerError, format!("Server error: {}", msg)),
+        Error::InvalidInput(msg) => (ErrorCode::InvalidInput, format!("Invalid input: {}", msg)),
+        Error::InvalidResponse(msg) => (
+            ErrorCode::InvalidResponse,
+            format!("Invalid response: {}", msg),
+        ),
+        Error::InvalidToken(msg) => (ErrorCode::InvalidToken, format!("Invalid token: {}", msg)),
+        Error::InvalidApiKey(msg) => (
+            ErrorCode::InvalidApiKey,
+            format!("Invalid api key: {}", msg),
+        ),
+        Error::JsonError(err) => (ErrorCode::JsonError, format!("JSON error: {}", err)),
+        Error::OtherError(msg) => (ErrorCode::OtherError, format!("Other error: {}", msg)),
     }
 }
 
+/// 获取错误对应的错误码（与转换为 `FfiError` 后的错误码一致）
+pub(crate) fn error_code(e: &Error) -> ErrorCode {
+    let (code, message) = describe(e);
+    ErrorDetails::parse(code, &message).refine_code(code, &message)
+}
+
+/// 错误是否可重试（与转换为 `FfiError` 后错误详情中的 `retryable` 一致）
+pub(crate) fn is_retryable(e: &Error) -> bool {
+    let (code, message) = describe(e);
+    let mut details = ErrorDetails::parse(code, &message);
+    details.refine_code(code, &message);
+    details.retryable
+}
+
 impl From<Error> for FfiError {
     fn from(e: Error) -> Self {
-        let (code, message_str) = match e {
-            Error::WsError(err) => (ErrorCode::WsError, format!("Websocket Error: {}", err)),
-            Error::HttpError(err) => (ErrorCode::HttpError, format!("HTTP error: {}", err)),
-            Error::ServerError(msg) => (ErrorCode::ServerError, format!("Server error: {}", msg)),
-            Error::InvalidInput(msg) => {
-                (ErrorCode::InvalidInput, format!("Invalid input: {}", msg))
-            }
-            Error::InvalidResponse(msg) => (
-                ErrorCode::InvalidResponse,
-                format!("Invalid response: {}", msg),
-            ),
-            Error::InvalidToken(msg) => {
-                (ErrorCode::InvalidToken, format!("Invalid token: {}", msg))
-            }
-            Error::InvalidApiKey(msg) => (
-                ErrorCode::InvalidApiKey,
-                format!("Invalid api key: {}", msg),
-            ),
-            Error::JsonError(err) => (ErrorCode::JsonError, format!("JSON error: {}", err)),
-            Error::OtherError(msg) => (ErrorCode::OtherError, format!("Other error: {}", msg)),
-        };
-
-        let message_cstr = match CString::new(message_str) {
-            Ok(s) => s.into_raw(),
-            Err(_) => std::ptr::null_mut(),
-        };
+        let (code, message_str) = describe(&e);
 
-        Self {
-            code,
-            message: message_cstr,
-        }
+        let mut details = ErrorDetails::parse(code, &message_str);
+        let code = details.refine_code(code, &message_str);
+        Self::with_message(code, message_str).with_details(details)
     }
 }
 
@@ -91,5 +250,15 @@ pub extern "C" fn transcribe_ffi_free_error(e: *mut FfiError) {
     }
     unsafe {
         free_c_string(&mut (*e).message);
+        free_details(&mut (*e).details);
+    }
+}
+
+fn free_details(details: &mut *mut ErrorDetails) {
+    if !details.is_null() {
+        unsafe {
+            let _ = Box::from_raw(*details);
+        }
+        *details = std::ptr::null_mut();
     }
 }
@@ -0,0 +1,300 @@
+use std::ffi::{c_char, c_int, CString};
+
+use crate::error::{ErrorCode, FfiError};
+use crate::types_ffi::free_c_string;
+
+/// 错误的结构化详情，随 `FfiError` 分配，`transcribe_ffi_free_error` 时释放
+///
+/// SDK 的错误类型只携带错误信息字符串（`HttpError` / `WsError` 内部的错误也只能格式化为字符串），
+/// 因此 HTTP 状态码、请求 ID 和 Retry-After 都是从错误信息中按常见格式解析的，
+/// 格式不符时保持为 0 / None。
+///
+/// 注意：这是一个不透明的指针类型，C 代码应通过 `transcribe_ffi_error_details` 读取
+#[derive(Clone, Default)]
+pub struct ErrorDetails {
+    pub(crate) http_status: u16,
+    pub(crate) request_id: Option<String>,
+    pub(crate) retryable: bool,
+    pub(crate) retry_after_ms: u64,
+    pub(crate) required_size: usize,
+    pub(crate) attempts: u32,
+}
+
+impl ErrorDetails {
+    /// 从错误码和错误信息中尽力解析详情
+    pub fn parse(code: ErrorCode, message: &str) -> Self {
+        let http_status = parse_http_status(message);
+        let retryable = match code {
+            ErrorCode::WsError => true,
+            ErrorCode::HttpError => {
+                http_status == 0 || http_status == 408 || http_status == 429 || http_status >= 500
+            }
+            ErrorCode::ServerError => {
+                http_status == 408 || http_status == 429 || http_status >= 500
+            }
+            _ => false,
+        };
+
+        Self {
+            http_status,
+            request_id: value_after(
+                message,
+                &[
+                    "request_id",
+                    "request-id",
+                    "requestid",
+                    "trace_id",
+                    "trace-id",
+                    "traceid",
+                ],
+            )
+            .map(str::to_string),
+            retryable,
+            retry_after_ms: value_after(message, &["retry_after", "retry-after"])
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(|secs| secs.saturating_mul(1000))
+                .unwrap_or(0),
+            required_size: 0,
+            attempts: 0,
+        }
+    }
+
+    /// 根据 HTTP 状态码和错误信息细化网络类错误的错误码
+    pub fn refine_code(&mut self, code: ErrorCode, message: &str) -> ErrorCode {
+        if !matches!(
+            code,
+            ErrorCode::WsError | ErrorCode::HttpError | ErrorCode::ServerError
+        ) {
+            return code;
+        }
+
+        match self.http_status {
+            401 | 403 => ErrorCode::Unauthorized,
+            404 => ErrorCode::NotFound,
+            429 => ErrorCode::RateLimited,
+            408 => ErrorCode::Timeout,
+            _ if code != ErrorCode::ServerError
+                && message.to_ascii_lowercase().contains("timed out") =>
+            {
+                self.retryable = true;
+                ErrorCode::Timeout
+            }
+            _ => code,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.http_status == 0
+            && self.request_id.is_none()
+            && !self.retryable
+            && self.retry_after_ms == 0
+            && self.required_size == 0
+            && self.attempts == 0
+    }
+}
+
+/// 解析 HTTP 状态码，支持 `status: 404` 和 reqwest 的 `(404 Not Found)` 两种形式
+fn parse_http_status(text: &str) -> u16 {
+    let valid = |v: u16| (100..=599).contains(&v);
+    if let Some(v) = value_after(text, &["http_status", "status_code", "status"])
+        .and_then(|v| v.parse().ok())
+        .filter(|v| valid(*v))
+    {
+        return v;
+    }
+
+    let bytes = text.as_bytes();
+    for (i, _) in text.match_indices('(') {
+        let digits = &bytes[i + 1..];
+        if digits.len() >= 4 && digits[..3].iter().all(u8::is_ascii_digit) && digits[3] == b' ' {
+            if let Some(v) = text[i + 1..i + 4].parse().ok().filter(|v| valid(*v)) {
+                return v;
+            }
+        }
+    }
+    0
+}
+
+/// 查找形如 `key: value`、`key=value` 或 `"key": "value"` 的值
+///
+/// 键不区分大小写，且前后都必须是单词边界（不能紧跟字母、数字、`_` 或 `-`），
+/// 以免 `code` 匹配到 `status_code` 中
+fn value_after<'a>(text: &'a str, keys: &[&str]) -> Option<&'a str> {
+    let lower = text.to_ascii_lowercase();
+    for key in keys {
+        for (pos, _) in lower.match_indices(key) {
+            let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+            let preceding = lower[..pos].chars().next_back();
+            let following = lower[pos + key.len()..].chars().next();
+            if preceding.is_some_and(is_word) || following.is_some_and(is_word) {
+                continue;
+            }
+
+            let rest = &text[pos + key.len()..];
+            let value = rest.trim_start_matches(|c: char| {
+                c == '"' || c == '\'' || c == ':' || c == '=' || c.is_whitespace()
+            });
+            let separator = &rest[..rest.len() - value.len()];
+            if !separator.contains([':', '=']) {
+                continue;
+            }
+
+            let end = value
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(value.len());
+            if end > 0 {
+                return Some(&value[..end]);
+            }
+        }
+    }
+    None
+}
+
+/// 错误的结构化详情
+///
+/// `http_status`、`request_id` 和 `retry_after_ms` 是从错误信息中尽力解析的，不保证存在，
+/// 调用方不应依赖它们做关键判断；`retryable` 由错误码和解析出的状态码推断。
+/// SDK 的错误不携带服务端的业务错误码，因此这里不提供 server_code，需要时请查看错误信息。
+#[repr(C)]
+pub struct FfiErrorDetails {
+    /// HTTP 状态码（从错误信息中解析，0 表示未知）
+    pub http_status: u16,
+    /// 请求 ID / trace ID（从错误信息中解析，可能为 null）
+    pub request_id: *mut c_char,
+    /// 是否值得重试（网络错误、408、429、5xx）
+    pub retryable: bool,
+    /// 服务端建议的重试等待时间（毫秒，从错误信息中解析），0 表示未提供
+    pub retry_after_ms: u64,
+    /// 错误码为 `BufferTooSmall` 时所需的缓冲区大小
+    pub required_size: usize,
+    /// 请求的尝试次数（包括首次请求，经过重试逻辑的请求才会填写），0 表示未知
+    pub attempts: u32,
+}
+
+impl From<Option<&ErrorDetails>> for FfiErrorDetails {
+    fn from(details: Option<&ErrorDetails>) -> Self {
+        FfiErrorDetails {
+            http_status: details.map_or(0, |d| d.http_status),
+            request_id: details
+                .and_then(|d| d.request_id.clone())
+                .and_then(|s| CString::new(s).ok())
+                .map(|s| s.into_raw())
+                .unwrap_or(std::ptr::null_mut()),
+            retryable: details.is_some_and(|d| d.retryable),
+            retry_after_ms: details.map_or(0, |d| d.retry_after_ms),
+            required_size: details.map_or(0, |d| d.required_size),
+            attempts: details.map_or(0, |d| d.attempts),
+        }
+    }
+}
+
+/// 获取错误的结构化详情
+///
+/// 详情随错误一起保存，从 SDK 返回的错误信息中尽力解析，无法解析的字段保持为 0 / null。
+/// 需在 `transcribe_ffi_free_error` 之前调用，结果需调用 `transcribe_ffi_free_error_details` 释放。
+///
+/// # 参数
+/// - `error`: 错误信息结构体指针
+/// - `out_details`: 输出错误详情结构体指针
+///
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_error_details(
+    error: *const FfiError,
+    out_details: *mut FfiErrorDetails,
+) -> c_int {
+    let Some(error) = (unsafe { error.as_ref() }) else {
+        return ErrorCode::InvalidInput as c_int;
+    };
+    if out_details.is_null() {
+        return ErrorCode::InvalidInput as c_int;
+    }
+
+    unsafe {
+        *out_details = FfiErrorDetails::from(error.details());
+    }
+
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_error_details(d: *mut FfiErrorDetails) {
+    if d.is_null() {
+        return;
+    }
+    unsafe {
+        free_c_string(&mut (*d).request_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_after_requires_word_boundaries() {
+        assert_eq!(value_after("status_code: 404", &["code"]), None);
+        assert_eq!(value_after("error-code=7", &["code"]), None);
+        assert_eq!(value_after("retry_after_ms: 5", &["retry_after"]), None);
+        assert_eq!(value_after("code: 7", &["code"]), Some("7"));
+        assert_eq!(
+            value_after(r#"{"Request_Id": "ab-1"}"#, &["request_id"]),
+            Some("ab-1")
+        );
+        assert_eq!(value_after("status 404", &["status"]), None);
+    }
+
+    #[test]
+    fn parse_http_status_forms() {
+        assert_eq!(parse_http_status("status_code: 404"), 404);
+        assert_eq!(parse_http_status("http_status=503"), 503);
+        assert_eq!(
+            parse_http_status("HTTP status client error (429 Too Many Requests) for url"),
+            429
+        );
+        assert_eq!(parse_http_status("status: 42"), 0);
+        assert_eq!(parse_http_status("connection reset"), 0);
+    }
+
+    #[test]
+    fn parse_details() {
+        let d = ErrorDetails::parse(
+            ErrorCode::ServerError,
+            "Server error: status_code: 503, request_id: r-1, retry_after: 2",
+        );
+        assert_eq!(d.http_status, 503);
+        assert_eq!(d.request_id.as_deref(), Some("r-1"));
+        assert!(d.retryable);
+        assert_eq!(d.retry_after_ms, 2000);
+
+        let d = ErrorDetails::parse(ErrorCode::ServerError, "Server error: status_code: 400");
+        assert!(!d.retryable);
+        assert_eq!(d.retry_after_ms, 0);
+    }
+
+    #[test]
+    fn refine_code_by_status() {
+        let message = "HTTP error: status_code: 404";
+        let mut d = ErrorDetails::parse(ErrorCode::HttpError, message);
+        assert_eq!(
+            d.refine_code(ErrorCode::HttpError, message),
+            ErrorCode::NotFound
+        );
+
+        let message = "HTTP error: operation timed out";
+        let mut d = ErrorDetails::parse(ErrorCode::HttpError, message);
+        assert_eq!(
+            d.refine_code(ErrorCode::HttpError, message),
+            ErrorCode::Timeout
+        );
+        assert!(d.retryable);
+
+        let message = "Invalid input: status: 404";
+        let mut d = ErrorDetails::parse(ErrorCode::InvalidInput, message);
+        assert_eq!(
+            d.refine_code(ErrorCode::InvalidInput, message),
+            ErrorCode::InvalidInput
+        );
+    }
+}
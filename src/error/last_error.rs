@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+
+use crate::error::{ErrorCode, ErrorDetails, FfiError, FfiErrorDetails};
+
+/// 最近一次失败调用的错误
+struct LastError {
+    code: ErrorCode,
+    message: CString,
+    details: Option<ErrorDetails>,
+}
+
+thread_local! {
+    /// 当前线程最近一次失败调用的错误
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+/// 记录当前线程的最近错误
+pub(crate) fn set_last_error(error: &FfiError) {
+    let message = if error.message.is_null() {
+        CString::default()
+    } else {
+        unsafe { CStr::from_ptr(error.message) }.to_owned()
+    };
+    let last_error = LastError {
+        code: error.code,
+        message,
+        details: error.details().cloned(),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(last_error));
+}
+
+/// 获取当前线程最近一次失败调用的错误码
+///
+/// 与 errno 类似，成功的调用不会清除最近错误。
+///
+/// # 返回
+/// 错误码，没有错误时返回 0
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_last_error_code() -> c_int {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, |e| e.code as c_int))
+}
+
+/// 获取当前线程最近一次失败调用的错误信息
+///
+/// 返回的字符串归本库所有，无需释放，在当前线程下一次失败调用或
+/// `transcribe_ffi_clear_last_error` 之前有效。
+///
+/// # 返回
+/// 错误信息（C 字符串），没有错误时返回 null
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |e| e.message.as_ptr())
+    })
+}
+
+/// 获取当前线程最近一次失败调用的错误详情
+///
+/// 与 `transcribe_ffi_error_details` 相同，结果需调用 `transcribe_ffi_free_error_details` 释放。
+///
+/// # 参数
+/// - `out_details`: 输出错误详情结构体指针
+///
+/// # 返回
+/// 错误码（0 表示成功，没有最近错误时所有字段为 0 / null）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_last_error_details(out_details: *mut FfiErrorDetails) -> c_int {
+    if out_details.is_null() {
+        return ErrorCode::InvalidInput as c_int;
+    }
+    LAST_ERROR.with(|last| {
+        let last = last.borrow();
+        let details = last.as_ref().and_then(|e| e.details.as_ref());
+        unsafe {
+            *out_details = FfiErrorDetails::from(details);
+        }
+    });
+    0
+}
+
+/// 清除当前线程的最近错误
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
@@ -2,12 +2,28 @@
 //!
 //! 此模块将 Rust 的异步 API 包装为同步的 C 兼容函数，供 Go 通过 cgo 调用。
 
+mod bilingual;
+mod client;
 mod error;
+mod logging;
+mod metrics;
+mod operation;
+mod panic;
+mod poll;
+mod postprocess;
+mod retry;
 mod runtime;
+mod speaker_stats;
+mod subtitle;
+mod token;
 mod transcribe_api;
+mod transcribe_async;
 mod transcribe_stream;
 mod types_ffi;
+mod upload;
 mod utils;
+mod watcher;
 
+pub use client::DianyaClient;
 pub use error::*;
 pub use types_ffi::*;
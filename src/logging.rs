@@ -0,0 +1,270 @@
+use std::ffi::*;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use common::Error;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::{error::FfiError, operation::UserData, utils::ffi_execute};
+
+/// 日志级别
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FfiLogLevel {
+    /// 关闭日志
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl From<FfiLogLevel> for LevelFilter {
+    fn from(level: FfiLogLevel) -> Self {
+        match level {
+            FfiLogLevel::Off => LevelFilter::Off,
+            FfiLogLevel::Error => LevelFilter::Error,
+            FfiLogLevel::Warn => LevelFilter::Warn,
+            FfiLogLevel::Info => LevelFilter::Info,
+            FfiLogLevel::Debug => LevelFilter::Debug,
+            FfiLogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+impl From<Level> for FfiLogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => FfiLogLevel::Error,
+            Level::Warn => FfiLogLevel::Warn,
+            Level::Info => FfiLogLevel::Info,
+            Level::Debug => FfiLogLevel::Debug,
+            Level::Trace => FfiLogLevel::Trace,
+        }
+    }
+}
+
+/// 日志回调
+///
+/// 参数依次为日志级别、来源模块、日志内容（已脱敏）和 user_data，字符串只在回调期间有效。
+/// 回调可能在任意线程（包括 runtime 工作线程）上调用。
+pub type FfiLogCallback = Option<
+    extern "C" fn(
+        level: FfiLogLevel,
+        target: *const c_char,
+        message: *const c_char,
+        user_data: *mut c_void,
+    ),
+>;
+
+static LOG_CALLBACK: Mutex<(FfiLogCallback, UserData)> =
+    Mutex::new((None, UserData::new(std::ptr::null_mut())));
+
+/// 本库的 logger 是否已成功安装
+static LOGGER_INSTALLED: OnceLock<bool> = OnceLock::new();
+
+/// 需要脱敏的字段名（不区分大小写）
+const SECRET_KEYS: &[&str] = &[
+    "bearer",
+    "authorization",
+    "access_token",
+    "api_key",
+    "apikey",
+    "token",
+];
+
+/// 将日志转发给宿主回调的 logger
+struct FfiLogger;
+
+impl Log for FfiLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let (callback, user_data) = *LOG_CALLBACK.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(callback) = callback else {
+            return;
+        };
+
+        let to_c = |s: &str| CString::new(s.replace('\0', "")).unwrap_or_default();
+        let target = to_c(record.target());
+        let message = to_c(&redact(&record.args().to_string()));
+        callback(
+            record.level().into(),
+            target.as_ptr(),
+            message.as_ptr(),
+            user_data.get(),
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+/// 隐藏日志中的 token 等敏感信息
+///
+/// 匹配 `token=xxx`、`"token": "xxx"`、`Bearer xxx` 等形式，将值替换为 `***`。
+/// 以敏感字段名结尾的字段（如 `mytoken=xxx`、`x_access_token=xxx`）同样隐藏。
+/// 只有 `Bearer` 后可以直接以空白分隔（且前面必须是单词边界），其他字段名后必须有 `:` 或 `=`，
+/// 以免误伤 `Token refreshed` 这类普通文本
+pub(crate) fn redact(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let matched = SECRET_KEYS.iter().find_map(|key| {
+            if !lower[pos..].starts_with(key) {
+                return None;
+            }
+            let is_bearer = *key == "bearer";
+            let preceding = lower[..pos].chars().next_back();
+            let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+            if is_bearer && preceding.is_some_and(is_word) {
+                return None;
+            }
+
+            let rest = &text[pos + key.len()..];
+            let value = rest.trim_start_matches(|c: char| {
+                c == '"' || c == '\'' || c == ':' || c == '=' || c.is_whitespace()
+            });
+            let separator = &rest[..rest.len() - value.len()];
+            let separated = if is_bearer {
+                !separator.is_empty()
+            } else {
+                separator.contains([':', '='])
+            };
+            if !separated {
+                return None;
+            }
+            // 值本身以另一个字段名开头（如 `Authorization: Bearer xxx`），交给该字段处理
+            let value_lower = value.to_ascii_lowercase();
+            if SECRET_KEYS.iter().any(|k| value_lower.starts_with(k)) {
+                return None;
+            }
+
+            let end = value
+                .find(|c: char| {
+                    c.is_whitespace() || matches!(c, '"' | '\'' | ',' | '&' | '}' | ')')
+                })
+                .unwrap_or(value.len());
+            (end > 0).then(|| (key.len() + separator.len(), end))
+        });
+
+        match matched {
+            Some((prefix_len, value_len)) => {
+                out.push_str(&text[pos..pos + prefix_len]);
+                out.push_str("***");
+                pos += prefix_len + value_len;
+            }
+            None => {
+                let c = text[pos..].chars().next().unwrap();
+                out.push(c);
+                pos += c.len_utf8();
+            }
+        }
+    }
+
+    out
+}
+
+/// 设置日志回调
+///
+/// 本库、运行时以及底层 SDK 的日志都会转发给回调，token 等敏感信息会被隐藏。
+/// 依赖中通过 `tracing` 输出的事件在进程内没有 tracing subscriber 时会转为日志一并转发。
+/// 宿主进程中已有其他 Rust logger 时无法安装，返回错误。
+///
+/// # 参数
+/// - `level`: 最低日志级别
+/// - `callback`: 日志回调，传 null 取消
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_log_callback(
+    level: FfiLogLevel,
+    callback: FfiLogCallback,
+    user_data: *mut c_void,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        static LOGGER: FfiLogger = FfiLogger;
+        if !*LOGGER_INSTALLED.get_or_init(|| log::set_logger(&LOGGER).is_ok()) {
+            return Err(Error::OtherError(
+                "Another logger is already installed".to_string(),
+            ));
+        }
+
+        *LOG_CALLBACK.lock().unwrap_or_else(PoisonError::into_inner) =
+            (callback, UserData::new(user_data));
+        log::set_max_level(if callback.is_some() {
+            level.into()
+        } else {
+            LevelFilter::Off
+        });
+
+        Ok(())
+    })
+}
+
+/// 调整日志级别
+///
+/// # 参数
+/// - `level`: 最低日志级别
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_log_level(level: FfiLogLevel) {
+    log::set_max_level(level.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_key_value_pairs() {
+        assert_eq!(redact("token=abc123&x=1"), "token=***&x=1");
+        assert_eq!(
+            redact(r#"{"access_token": "abc", "n": 1}"#),
+            r#"{"access_token": "***", "n": 1}"#
+        );
+        assert_eq!(redact("api_key = k1, next"), "api_key = ***, next");
+        assert_eq!(redact("APIKEY:k1"), "APIKEY:***");
+    }
+
+    #[test]
+    fn redacts_prefixed_keys() {
+        assert_eq!(redact("mytoken=abc"), "mytoken=***");
+        assert_eq!(redact("x_access_token=abc&y=2"), "x_access_token=***&y=2");
+        assert_eq!(redact(r#"{"x-api_key": "k"}"#), r#"{"x-api_key": "***"}"#);
+        assert_eq!(redact("refreshToken: r1"), "refreshToken: ***");
+    }
+
+    #[test]
+    fn redacts_bearer_tokens() {
+        assert_eq!(
+            redact("Authorization: Bearer abc.def"),
+            "Authorization: Bearer ***"
+        );
+        assert_eq!(redact("bearer abc"), "bearer ***");
+    }
+
+    #[test]
+    fn keeps_plain_text() {
+        for text in [
+            "Token refreshed by provider",
+            "Token provider returned no token",
+            "Bearer token expired",
+            "nobearer abc",
+            "tokens: 5",
+            "token_type=bearer",
+            "Token",
+        ] {
+            assert_eq!(redact(text), text);
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::ffi::*;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use common::Error;
+
+use crate::error::{error_code, ErrorCode, FfiError};
+use crate::utils::ffi_execute;
+
+/// 统计指标的操作类型
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Operation {
+    Upload,
+    GetStatus,
+    Export,
+    TranslateText,
+    TranslateUtterance,
+    TranslateTranscribe,
+    WsWriteTxt,
+    WsWriteBytes,
+    WsReceive,
+}
+
+impl Operation {
+    const ALL: [Operation; 9] = [
+        Operation::Upload,
+        Operation::GetStatus,
+        Operation::Export,
+        Operation::TranslateText,
+        Operation::TranslateUtterance,
+        Operation::TranslateTranscribe,
+        Operation::WsWriteTxt,
+        Operation::WsWriteBytes,
+        Operation::WsReceive,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Upload => "upload",
+            Operation::GetStatus => "get_status",
+            Operation::Export => "export",
+            Operation::TranslateText => "translate_text",
+            Operation::TranslateUtterance => "translate_utterance",
+            Operation::TranslateTranscribe => "translate_transcribe",
+            Operation::WsWriteTxt => "ws_write_txt",
+            Operation::WsWriteBytes => "ws_write_bytes",
+            Operation::WsReceive => "ws_receive",
+        }
+    }
+}
+
+/// 单个操作的计数
+#[derive(Clone)]
+struct OpMetrics {
+    requests: u64,
+    errors: BTreeMap<ErrorCode, u64>,
+    latency_total: Duration,
+    latency_max: Duration,
+    bytes_sent: u64,
+    bytes_received: u64,
+    messages_sent: u64,
+    messages_received: u64,
+}
+
+impl OpMetrics {
+    const EMPTY: OpMetrics = OpMetrics {
+        requests: 0,
+        errors: BTreeMap::new(),
+        latency_total: Duration::ZERO,
+        latency_max: Duration::ZERO,
+        bytes_sent: 0,
+        bytes_received: 0,
+        messages_sent: 0,
+        messages_received: 0,
+    };
+
+    fn write_json(&self, out: &mut String) {
+        let avg_us = if self.requests == 0 {
+            0
+        } else {
+            self.latency_total.as_micros() / self.requests as u128
+        };
+
+        let _ = write!(out, "{{\"requests\":{},\"errors\":{{", self.requests);
+        for (i, (code, count)) in self.errors.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "\"{:?}\":{}", code, count);
+        }
+        let _ = write!(
+            out,
+            "}},\"latency_total_us\":{},\"latency_avg_us\":{},\"latency_max_us\":{},\
+             \"bytes_sent\":{},\"bytes_received\":{},\
+             \"messages_sent\":{},\"messages_received\":{}}}",
+            self.latency_total.as_micros(),
+            avg_us,
+            self.latency_max.as_micros(),
+            self.bytes_sent,
+            self.bytes_received,
+            self.messages_sent,
+            self.messages_received
+        );
+    }
+}
+
+static METRICS: Mutex<[OpMetrics; Operation::ALL.len()]> =
+    Mutex::new([OpMetrics::EMPTY; Operation::ALL.len()]);
+
+fn with_metrics<R>(op: Operation, f: impl FnOnce(&mut OpMetrics) -> R) -> R {
+    let mut metrics = METRICS.lock().unwrap_or_else(PoisonError::into_inner);
+    f(&mut metrics[op as usize])
+}
+
+/// 记录一次调用
+pub(crate) fn record_call(op: Operation, latency: Duration, error: Option<ErrorCode>) {
+    with_metrics(op, |m| {
+        m.requests += 1;
+        m.latency_total += latency;
+        m.latency_max = m.latency_max.max(latency);
+        if let Some(code) = error {
+            *m.errors.entry(code).or_default() += 1;
+        }
+    });
+}
+
+/// 记录传输的字节数
+pub(crate) fn record_bytes(op: Operation, bytes_sent: usize, bytes_received: usize) {
+    with_metrics(op, |m| {
+        m.bytes_sent += bytes_sent as u64;
+        m.bytes_received += bytes_received as u64;
+    });
+}
+
+/// 记录一条发送（`sent` 为 true）或接收的 WebSocket 消息
+pub(crate) fn record_message(op: Operation, sent: bool, bytes: usize) {
+    with_metrics(op, |m| {
+        if sent {
+            m.bytes_sent += bytes as u64;
+            m.messages_sent += 1;
+        } else {
+            m.bytes_received += bytes as u64;
+            m.messages_received += 1;
+        }
+    });
+}
+
+/// 一层 [`timeout`] 的超时标记，超时后在丢弃请求之前置位
+struct TimeoutScope {
+    fired: AtomicBool,
+    /// 外层的超时范围
+    parent: Option<Arc<TimeoutScope>>,
+}
+
+impl TimeoutScope {
+    /// 本层或任一外层是否已超时
+    fn fired(&self) -> bool {
+        self.fired.load(Ordering::Acquire) || self.parent.as_ref().is_some_and(|p| p.fired())
+    }
+}
+
+tokio::task_local! {
+    /// 请求所在的最内层超时范围
+    static TIMEOUT_SCOPE: Arc<TimeoutScope>;
+}
+
+/// 为请求设置超时，与 `tokio::time::timeout` 相同，但超时丢弃的请求计为 `Timeout` 错误
+pub(crate) async fn timeout<F: Future>(
+    duration: Duration,
+    fut: F,
+) -> Result<F::Output, tokio::time::error::Elapsed> {
+    let scope = Arc::new(TimeoutScope {
+        fired: AtomicBool::new(false),
+        parent: TIMEOUT_SCOPE.try_with(Arc::clone).ok(),
+    });
+    let mut fut = Box::pin(TIMEOUT_SCOPE.scope(scope.clone(), fut));
+    let result = tokio::time::timeout(duration, &mut fut).await;
+    if result.is_err() {
+        scope.fired.store(true, Ordering::Release);
+        drop(fut);
+    }
+    result
+}
+
+/// 统计一次请求的次数、延迟和错误
+///
+/// 请求在完成前被丢弃时计为 `Cancelled` 错误，经 [`timeout`] 超时丢弃时计为 `Timeout` 错误
+pub(crate) async fn track<T, F>(op: Operation, fut: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    struct Pending {
+        op: Operation,
+        started: Instant,
+        done: bool,
+        /// 请求开始时所在的超时范围
+        scope: Option<Arc<TimeoutScope>>,
+    }
+
+    impl Drop for Pending {
+        fn drop(&mut self) {
+            if !self.done {
+                let code = if self.scope.as_ref().is_some_and(|s| s.fired()) {
+                    ErrorCode::Timeout
+                } else {
+                    ErrorCode::Cancelled
+                };
+                record_call(self.op, self.started.elapsed(), Some(code));
+            }
+        }
+    }
+
+    let mut pending = Pending {
+        op,
+        started: Instant::now(),
+        done: false,
+        scope: TIMEOUT_SCOPE.try_with(Arc::clone).ok(),
+    };
+    let result = fut.await;
+    pending.done = true;
+    record_call(
+        op,
+        pending.started.elapsed(),
+        result.as_ref().err().map(error_code),
+    );
+    result
+}
+
+/// 统计上传请求，成功时按文件大小计入上传字节数
+pub(crate) async fn track_upload<T, F>(filepath: &str, fut: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let size = std::fs::metadata(filepath).map_or(0, |m| m.len() as usize);
+    let result = track(Operation::Upload, fut).await;
+    if result.is_ok() {
+        record_bytes(Operation::Upload, size, 0);
+    }
+    result
+}
+
+/// 统计导出请求，成功时计入下载字节数
+pub(crate) async fn track_export<F>(fut: F) -> Result<Vec<u8>, Error>
+where
+    F: Future<Output = Result<Vec<u8>, Error>>,
+{
+    let result = track(Operation::Export, fut).await;
+    if let Ok(data) = &result {
+        record_bytes(Operation::Export, 0, data.len());
+    }
+    result
+}
+
+/// 将当前统计指标序列化为 JSON
+fn snapshot_json() -> String {
+    let snapshot = METRICS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+
+    let mut json = String::from("{");
+    for (i, op) in Operation::ALL.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        let _ = write!(json, "\"{}\":", op.name());
+        snapshot[*op as usize].write_json(&mut json);
+    }
+    json.push('}');
+    json
+}
+
+/// 获取统计指标快照（JSON）
+///
+/// 以操作名（"upload"、"get_status"、"export"、"translate_text"、"translate_utterance"、
+/// "translate_transcribe"、"ws_write_txt"、"ws_write_bytes"、"ws_receive"）为键，每项包含
+/// 请求数 `requests`、按错误码名统计的错误数 `errors`、延迟（微秒）`latency_total_us` /
+/// `latency_avg_us` / `latency_max_us`，以及 `bytes_sent`、`bytes_received`、
+/// `messages_sent`、`messages_received`。
+///
+/// # 参数
+/// - `out_json`: 输出 JSON 字符串指针，需调用 `transcribe_ffi_free_string` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_metrics_snapshot(
+    out_json: *mut *mut c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_json.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+
+        let json = CString::new(snapshot_json()).map_err(|e| Error::OtherError(e.to_string()))?;
+        unsafe {
+            *out_json = json.into_raw();
+        }
+
+        Ok(())
+    })
+}
+
+/// 清零所有统计指标
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_metrics_reset() {
+    *METRICS.lock().unwrap_or_else(PoisonError::into_inner) =
+        [OpMetrics::EMPTY; Operation::ALL.len()];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_count(op: Operation, code: ErrorCode) -> u64 {
+        with_metrics(op, |m| m.errors.get(&code).copied().unwrap_or(0))
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(fut)
+    }
+
+    #[test]
+    fn write_json_shape() {
+        let mut m = OpMetrics::EMPTY;
+        m.requests = 2;
+        m.errors.insert(ErrorCode::Timeout, 1);
+        m.errors.insert(ErrorCode::HttpError, 1);
+        m.latency_total = Duration::from_millis(3);
+        m.latency_max = Duration::from_millis(2);
+        m.bytes_sent = 10;
+        m.messages_received = 4;
+
+        let mut out = String::new();
+        m.write_json(&mut out);
+        assert_eq!(
+            out,
+            "{\"requests\":2,\"errors\":{\"HttpError\":1,\"Timeout\":1},\
+             \"latency_total_us\":3000,\"latency_avg_us\":1500,\"latency_max_us\":2000,\
+             \"bytes_sent\":10,\"bytes_received\":0,\
+             \"messages_sent\":0,\"messages_received\":4}"
+        );
+
+        let mut out = String::new();
+        OpMetrics::EMPTY.write_json(&mut out);
+        assert!(out.starts_with("{\"requests\":0,\"errors\":{},"));
+        assert!(out.contains("\"latency_avg_us\":0,"));
+    }
+
+    #[test]
+    fn snapshot_lists_every_operation_in_order() {
+        let json = snapshot_json();
+        assert!(json.starts_with("{\"upload\":{\"requests\":"));
+        assert!(json.ends_with("}}"));
+        let mut last = 0;
+        for op in Operation::ALL {
+            let key = format!("\"{}\":{{\"requests\":", op.name());
+            let pos = json
+                .find(&key)
+                .unwrap_or_else(|| panic!("missing {}", op.name()));
+            assert!(pos >= last);
+            last = pos;
+        }
+    }
+
+    #[test]
+    fn timed_out_requests_count_as_timeout() {
+        let op = Operation::WsReceive;
+        let before = error_count(op, ErrorCode::Timeout);
+        let result = block_on(timeout(
+            Duration::from_millis(1),
+            track(op, std::future::pending::<Result<(), Error>>()),
+        ));
+        assert!(result.is_err());
+        assert_eq!(error_count(op, ErrorCode::Timeout), before + 1);
+
+        // 外层超时时，内层未超时的范围同样计为超时
+        let before = error_count(op, ErrorCode::Timeout);
+        let result = block_on(timeout(
+            Duration::from_millis(1),
+            timeout(
+                Duration::from_secs(60),
+                track(op, std::future::pending::<Result<(), Error>>()),
+            ),
+        ));
+        assert!(result.is_err());
+        assert_eq!(error_count(op, ErrorCode::Timeout), before + 1);
+    }
+
+    #[test]
+    fn dropped_requests_count_as_cancelled() {
+        let op = Operation::TranslateTranscribe;
+        let before = error_count(op, ErrorCode::Cancelled);
+        block_on(async {
+            let fut = track(op, std::future::pending::<Result<(), Error>>());
+            // 轮询一次后丢弃
+            let _ = tokio::time::timeout(Duration::from_millis(1), fut).await;
+        });
+        assert_eq!(error_count(op, ErrorCode::Cancelled), before + 1);
+    }
+}
@@ -0,0 +1,435 @@
+use std::ffi::{c_int, c_void};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use common::Error;
+use tokio::task::AbortHandle;
+use transcribe::transcribe::UploadResponse;
+
+use crate::{
+    error::FfiError,
+    runtime::{self, get_runtime},
+    transcribe_ffi_free_error,
+    utils::ffi_execute,
+    FfiUploadResponse,
+};
+
+/// 异步操作 ID 计数器（0 保留为无效 ID）
+static NEXT_OP_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 宿主传入的 user_data 指针，原样回传给回调
+///
+/// 跨线程使用的安全性由宿主保证
+#[derive(Clone, Copy)]
+pub(crate) struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+
+impl UserData {
+    pub const fn new(p: *mut c_void) -> Self {
+        Self(p)
+    }
+
+    pub fn get(self) -> *mut c_void {
+        self.0
+    }
+}
+
+/// 异步操作的完成通知，任务未完成就被丢弃（runtime 关闭）时以 `Cancelled` 错误通知
+struct Completion<T, C: FnOnce(u64, Result<T, FfiError>)> {
+    op_id: u64,
+    complete: Option<C>,
+    _result: PhantomData<fn(T)>,
+}
+
+impl<T, C: FnOnce(u64, Result<T, FfiError>)> Completion<T, C> {
+    fn finish(mut self, result: Result<T, FfiError>) {
+        if let Some(complete) = self.complete.take() {
+            complete(self.op_id, result);
+        }
+    }
+}
+
+impl<T, C: FnOnce(u64, Result<T, FfiError>)> Drop for Completion<T, C> {
+    fn drop(&mut self) {
+        if let Some(complete) = self.complete.take() {
+            complete(self.op_id, Err(FfiError::cancelled()));
+        }
+    }
+}
+
+/// 在全局 runtime 上执行异步操作，完成后调用 `complete`
+///
+/// 操作 ID 在启动任务之前写入 `out_op_id`，`complete` 可能在本函数返回前就在 runtime 线程上
+/// 被调用，此时调用方已能看到操作 ID。runtime 已关闭时返回错误，不写入 ID 也不调用 `complete`
+pub(crate) fn spawn_async<T, E, Fut, C>(
+    out_op_id: &mut u64,
+    fut: Fut,
+    complete: C,
+) -> Result<(), FfiError>
+where
+    T: Send + 'static,
+    E: Into<FfiError>,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    C: FnOnce(u64, Result<T, FfiError>) + Send + 'static,
+{
+    let runtime = get_runtime()?;
+    let op_id = NEXT_OP_ID.fetch_add(1, Ordering::Relaxed);
+    *out_op_id = op_id;
+    let completion = Completion {
+        op_id,
+        complete: Some(complete),
+        _result: PhantomData,
+    };
+    runtime::spawn(&runtime, async move {
+        let result = fut.await;
+        completion.finish(result.map_err(Into::into));
+    });
+    Ok(())
+}
+
+/// 将结果交给回调：成功时传入结果指针，失败时传入错误指针
+///
+/// 两者都只在回调期间有效，回调返回后由本库释放
+pub(crate) fn deliver<T>(
+    result: Result<T, FfiError>,
+    free: extern "C" fn(*mut T),
+    notify: impl FnOnce(c_int, *const T, *const FfiError),
+) {
+    match result {
+        Ok(mut value) => {
+            notify(0, &value, std::ptr::null());
+            free(&mut value);
+        }
+        Err(e) => deliver_error(e, |code, error| notify(code, std::ptr::null(), error)),
+    }
+}
+
+/// 将错误交给回调，回调返回后释放错误信息
+pub(crate) fn deliver_error(mut error: FfiError, notify: impl FnOnce(c_int, *const FfiError)) {
+    notify(error.code as c_int, &error);
+    transcribe_ffi_free_error(&mut error);
+}
+
+/// 可取消操作的状态
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FfiOperationState {
+    /// 执行中
+    Pending,
+    /// 已成功完成
+    Completed,
+    /// 已失败
+    Failed,
+    /// 已取消
+    Cancelled,
+}
+
+/// 可取消操作的输出
+pub(crate) enum OpOutput {
+    Upload(UploadResponse),
+    Export(Vec<u8>),
+}
+
+struct OpInner {
+    state: FfiOperationState,
+    result: Option<Result<OpOutput, FfiError>>,
+}
+
+impl Drop for OpInner {
+    fn drop(&mut self) {
+        if let Some(Err(e)) = &mut self.result {
+            transcribe_ffi_free_error(e);
+        }
+    }
+}
+
+struct OpShared {
+    inner: Mutex<OpInner>,
+    done: Condvar,
+}
+
+impl OpShared {
+    fn lock(&self) -> MutexGuard<'_, OpInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 结束操作；若操作已结束（例如已取消）则忽略
+    fn finish(&self, state: FfiOperationState, result: Option<Result<OpOutput, FfiError>>) {
+        let mut inner = self.lock();
+        if inner.state == FfiOperationState::Pending {
+            inner.state = state;
+            inner.result = result;
+            self.done.notify_all();
+        } else if let Some(Err(mut e)) = result {
+            transcribe_ffi_free_error(&mut e);
+        }
+    }
+}
+
+/// 可取消的长时间操作句柄
+/// 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
+pub struct FfiOperation {
+    shared: Arc<OpShared>,
+    abort: AbortHandle,
+}
+
+impl FfiOperation {
+    /// 在全局 runtime 上启动可取消操作
+    pub(crate) fn spawn<E, Fut>(fut: Fut) -> Result<Self, FfiError>
+    where
+        E: Into<FfiError>,
+        Fut: Future<Output = Result<OpOutput, E>> + Send + 'static,
+    {
+        let runtime = get_runtime()?;
+        let shared = Arc::new(OpShared {
+            inner: Mutex::new(OpInner {
+                state: FfiOperationState::Pending,
+                result: None,
+            }),
+            done: Condvar::new(),
+        });
+
+        // 任务未完成就被丢弃（runtime 关闭）时将操作标记为已取消
+        struct CancelOnDrop(Arc<OpShared>);
+
+        impl Drop for CancelOnDrop {
+            fn drop(&mut self) {
+                self.0.finish(FfiOperationState::Cancelled, None);
+            }
+        }
+
+        let task_shared = CancelOnDrop(shared.clone());
+        let task = runtime::spawn(&runtime, async move {
+            let result = fut.await.map_err(Into::into);
+            let task_shared = &task_shared.0;
+            let state = if result.is_ok() {
+                FfiOperationState::Completed
+            } else {
+                FfiOperationState::Failed
+            };
+            task_shared.finish(state, Some(result));
+        });
+
+        Ok(Self {
+            shared,
+            abort: task.abort_handle(),
+        })
+    }
+
+    unsafe fn from_ptr<'a>(op: *const FfiOperation) -> Result<&'a Self, Error> {
+        op.as_ref()
+            .ok_or_else(|| Error::InvalidInput("Invalid operation handle".to_string()))
+    }
+
+    fn cancel(&self) {
+        self.abort.abort();
+        self.shared.finish(FfiOperationState::Cancelled, None);
+    }
+
+    fn state(&self) -> FfiOperationState {
+        self.shared.lock().state
+    }
+
+    fn wait(&self, timeout: Option<Duration>) -> FfiOperationState {
+        let guard = self.shared.lock();
+        let pending = |inner: &mut OpInner| inner.state == FfiOperationState::Pending;
+        let guard = match timeout {
+            Some(timeout) => {
+                self.shared
+                    .done
+                    .wait_timeout_while(guard, timeout, pending)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            None => self
+                .shared
+                .done
+                .wait_while(guard, pending)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+        guard.state
+    }
+
+    /// 访问已结束操作的结果
+    ///
+    /// 操作仍在执行或已取消时返回对应错误
+    fn with_result<R>(
+        &self,
+        f: impl FnOnce(&mut Option<Result<OpOutput, FfiError>>) -> Result<R, FfiError>,
+    ) -> Result<R, FfiError> {
+        let mut inner = self.shared.lock();
+        match inner.state {
+            FfiOperationState::Pending => {
+                Err(Error::InvalidInput("Operation is still pending".to_string()).into())
+            }
+            FfiOperationState::Cancelled => Err(FfiError::cancelled()),
+            FfiOperationState::Completed | FfiOperationState::Failed => f(&mut inner.result),
+        }
+    }
+}
+
+/// 取消操作
+///
+/// 正在执行的请求会在 runtime 上被丢弃，之后获取结果将返回 `Cancelled` 错误。
+/// 对已结束的操作调用无效果。
+///
+/// # 参数
+/// - `op`: 操作句柄
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_cancel(op: *const FfiOperation) {
+    if let Some(op) = unsafe { op.as_ref() } {
+        op.cancel();
+    }
+}
+
+/// 查询操作状态（不阻塞）
+///
+/// # 参数
+/// - `op`: 操作句柄
+///
+/// # 返回
+/// 操作状态；句柄为 null 时返回 `Failed`
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_poll(op: *const FfiOperation) -> FfiOperationState {
+    match unsafe { op.as_ref() } {
+        Some(op) => op.state(),
+        None => FfiOperationState::Failed,
+    }
+}
+
+/// 等待操作结束
+///
+/// # 参数
+/// - `op`: 操作句柄
+/// - `timeout_ms`: 超时时间（毫秒），0 表示一直等待
+///
+/// # 返回
+/// 等待结束时的操作状态；超时返回 `Pending`，句柄为 null 时返回 `Failed`
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_wait(
+    op: *const FfiOperation,
+    timeout_ms: u64,
+) -> FfiOperationState {
+    let timeout = if timeout_ms == 0 {
+        None
+    } else {
+        Some(Duration::from_millis(timeout_ms))
+    };
+    match unsafe { op.as_ref() } {
+        Some(op) => op.wait(timeout),
+        None => FfiOperationState::Failed,
+    }
+}
+
+/// 获取上传操作的结果
+///
+/// 结果只能获取一次，成功后需调用 `transcribe_ffi_free_upload_response` 释放
+///
+/// # 参数
+/// - `op`: 由 `transcribe_ffi_upload_start` 返回的操作句柄
+/// - `out_result`: 输出上传结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功，操作被取消时为 `Cancelled`）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_upload_result(
+    op: *const FfiOperation,
+    out_result: *mut FfiUploadResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let op = unsafe { FfiOperation::from_ptr(op)? };
+
+        let result = op.with_result(|slot| match slot.take() {
+            Some(Ok(OpOutput::Upload(result))) => Ok(result),
+            Some(Err(e)) => Err(e),
+            Some(other) => {
+                *slot = Some(other);
+                Err(Error::InvalidInput("Operation is not an upload".to_string()).into())
+            }
+            None => Err(Error::InvalidInput("Operation result already taken".to_string()).into()),
+        })?;
+
+        let ffi_result = FfiUploadResponse::try_from(result)?;
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 获取导出操作的结果
+///
+/// 缓冲区不足时返回错误并在 `result_len` 中写入所需大小，
+/// 数据仍保留在操作中，可用更大的缓冲区再次获取。
+///
+/// # 参数
+/// - `op`: 由 `transcribe_ffi_export_start` 返回的操作句柄
+/// - `result_data`: 输出二进制数据的缓冲区指针
+/// - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功，操作被取消时为 `Cancelled`）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_export_result(
+    op: *const FfiOperation,
+    result_data: *mut u8,
+    result_len: *mut usize,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if result_data.is_null() || result_len.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let op = unsafe { FfiOperation::from_ptr(op)? };
+
+        op.with_result(|slot| match slot {
+            Some(Ok(OpOutput::Export(data))) => {
+                let len = data.len();
+                let buffer_size = unsafe { *result_len };
+
+                if len > buffer_size {
+                    unsafe { *result_len = len };
+                    return Err(FfiError::buffer_too_small(len));
+                }
+
+                unsafe {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), result_data, len);
+                    *result_len = len;
+                }
+                Ok(())
+            }
+            Some(Ok(_)) => {
+                Err(Error::InvalidInput("Operation is not an export".to_string()).into())
+            }
+            Some(Err(_)) | None => match slot.take() {
+                Some(Err(e)) => Err(e),
+                _ => Err(Error::InvalidInput("Operation result already taken".to_string()).into()),
+            },
+        })
+    })
+}
+
+/// 释放操作句柄，仍在执行的操作会被取消
+///
+/// # 参数
+/// - `op`: 操作句柄
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_op_free(op: *mut FfiOperation) {
+    if op.is_null() {
+        return;
+    }
+
+    unsafe {
+        let op = Box::from_raw(op);
+        op.cancel();
+    }
+}
@@ -0,0 +1,163 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, Once, PoisonError};
+
+use crate::operation::UserData;
+
+/// 启用 panic 回溯的环境变量（值为 "1" 或 "true" 时启用）
+const BACKTRACE_ENV: &str = "DIANYAAPI_FFI_BACKTRACE";
+
+/// 回溯开关：0 表示未设置（读取环境变量），1 表示关闭，2 表示开启
+static BACKTRACE: AtomicU8 = AtomicU8::new(0);
+
+static HOOK: Once = Once::new();
+
+static PANIC_CALLBACK: Mutex<(FfiPanicCallback, UserData)> =
+    Mutex::new((None, UserData::new(std::ptr::null_mut())));
+
+/// panic 回调
+///
+/// 参数依次为 panic 信息、源码位置（"文件:行:列"，可能为 null）、
+/// 回溯（未启用时为 null）和 user_data，字符串只在回调期间有效。
+/// 回调可能在任意线程（包括 runtime 工作线程）上调用。
+pub type FfiPanicCallback = Option<
+    extern "C" fn(
+        message: *const c_char,
+        location: *const c_char,
+        backtrace: *const c_char,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 一次 panic 的信息
+struct PanicReport {
+    message: String,
+    location: Option<String>,
+    backtrace: Option<String>,
+}
+
+impl PanicReport {
+    fn render(&self) -> String {
+        let mut text = format!("Rust panic occurred: {}", self.message);
+        if let Some(location) = &self.location {
+            text.push_str(&format!(" at {}", location));
+        }
+        if let Some(backtrace) = &self.backtrace {
+            text.push_str(&format!("\nbacktrace:\n{}", backtrace));
+        }
+        text
+    }
+}
+
+thread_local! {
+    /// 当前线程最近一次 panic 的信息，由 panic hook 写入，`take_panic_message` 取出
+    static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+fn backtrace_enabled() -> bool {
+    match BACKTRACE.load(Ordering::Relaxed) {
+        1 => false,
+        2 => true,
+        _ => std::env::var(BACKTRACE_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false),
+    }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn forward_to_callback(report: &PanicReport) {
+    let (callback, user_data) = *PANIC_CALLBACK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let Some(callback) = callback else {
+        return;
+    };
+
+    let to_c = |s: &str| CString::new(s.replace('\0', "")).unwrap_or_default();
+    let message = to_c(&report.message);
+    let location = report.location.as_deref().map(to_c);
+    let backtrace = report.backtrace.as_deref().map(to_c);
+    callback(
+        message.as_ptr(),
+        location.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+        backtrace.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+        user_data.get(),
+    );
+}
+
+/// 安装进程级 panic hook（只安装一次，并保留之前的 hook）
+pub(crate) fn install_hook() {
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let report = PanicReport {
+                message: payload_message(info.payload()),
+                location: info
+                    .location()
+                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+                backtrace: backtrace_enabled().then(|| Backtrace::force_capture().to_string()),
+            };
+
+            log::error!("{}", report.render());
+            forward_to_callback(&report);
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
+            previous(info);
+        }));
+    });
+}
+
+/// 清除当前线程残留的 panic 信息
+pub(crate) fn clear_panic() {
+    LAST_PANIC.with(|last| last.borrow_mut().take());
+}
+
+/// 生成 panic 的错误信息，优先使用 panic hook 捕获的位置和回溯
+pub(crate) fn take_panic_message(payload: &(dyn Any + Send)) -> String {
+    LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or_else(|| PanicReport {
+            message: payload_message(payload),
+            location: None,
+            backtrace: None,
+        })
+        .render()
+}
+
+/// 设置是否在 panic 错误信息中附带回溯
+///
+/// 未调用时由环境变量 `DIANYAAPI_FFI_BACKTRACE` 决定（"1" 或 "true" 时启用）。
+///
+/// # 参数
+/// - `enabled`: 是否启用
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_panic_backtrace(enabled: bool) {
+    BACKTRACE.store(if enabled { 2 } else { 1 }, Ordering::Relaxed);
+}
+
+/// 设置 panic 回调，本库内任何线程发生 panic 时都会调用
+///
+/// # 参数
+/// - `callback`: panic 回调，传 null 取消
+/// - `user_data`: 原样传给回调的用户数据
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_panic_callback(
+    callback: FfiPanicCallback,
+    user_data: *mut c_void,
+) {
+    install_hook();
+    *PANIC_CALLBACK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = (callback, UserData::new(user_data));
+}
@@ -0,0 +1,370 @@
+use std::ffi::*;
+use std::time::Duration;
+
+use common::Error;
+use transcribe::transcribe::{status, TranscribeStatus, UploadResponse};
+
+use crate::{
+    error::{error_code, is_retryable, FfiError},
+    metrics::{self, track, Operation},
+    operation::UserData,
+    runtime::block_on,
+    token::with_token_refresh,
+    upload::{upload_file, FfiUploadOptions, UploadOptions},
+    utils::*,
+    FfiTranscribeStatus,
+};
+
+/// 默认的首次轮询间隔
+const DEFAULT_INITIAL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 默认的最大轮询间隔
+const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 默认的轮询间隔增长倍数
+const DEFAULT_BACKOFF_FACTOR: f64 = 1.5;
+
+/// 默认的总超时时间，避免服务端返回未知状态时无限轮询
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// 默认的终止状态（不区分大小写），与 `FfiPollConfig::terminal_statuses` 的文档保持一致
+///
+/// SDK 没有定义状态字符串的取值，这里是按常见写法列出的集合，并不保证完整
+const DEFAULT_TERMINAL_STATUSES: &[&str] = &[
+    "done",
+    "completed",
+    "complete",
+    "finished",
+    "success",
+    "succeeded",
+    "failed",
+    "failure",
+    "error",
+    "cancelled",
+    "canceled",
+];
+
+/// 默认表示失败的终止状态（不区分大小写），与 `FfiPollConfig::failure_statuses` 的文档保持一致
+const DEFAULT_FAILURE_STATUSES: &[&str] = &["failed", "failure", "error", "cancelled", "canceled"];
+
+/// 状态轮询配置
+///
+/// 调用方须先用 `transcribe_ffi_poll_config_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiPollConfig)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiPollConfig {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 首次轮询间隔（毫秒），0 表示使用默认值 2000
+    pub initial_interval_ms: u64,
+    /// 最大轮询间隔（毫秒），0 表示使用默认值 30000
+    pub max_interval_ms: u64,
+    /// 状态未变化时轮询间隔的增长倍数，小于 1 时使用默认值 1.5
+    pub backoff_factor: f64,
+    /// 总超时时间（毫秒），0 表示使用默认值 7200000（2 小时），`UINT64_MAX` 表示不设超时
+    pub timeout_ms: u64,
+    /// 终止状态数组（C 字符串数组，不区分大小写），为 null 时使用默认值：
+    /// "done"、"completed"、"complete"、"finished"、"success"、"succeeded"、
+    /// "failed"、"failure"、"error"、"cancelled"、"canceled"。
+    /// 默认值是猜测的常见写法而非服务端约定，建议显式列出服务端实际使用的终止状态；
+    /// 状态不在列表中时会一直轮询到超时
+    pub terminal_statuses: *const *const c_char,
+    /// 终止状态数量
+    pub terminal_statuses_len: usize,
+    /// 表示失败的终止状态数组（C 字符串数组，不区分大小写），任务监视器据此区分
+    /// `Completed` 和 `Failed` 事件。为 null 时使用默认值：
+    /// "failed"、"failure"、"error"、"cancelled"、"canceled"。
+    /// 自定义 `terminal_statuses` 时通常也需要一并设置
+    pub failure_statuses: *const *const c_char,
+    /// 失败状态数量
+    pub failure_statuses_len: usize,
+}
+
+/// 解析后的轮询配置
+#[derive(Clone)]
+pub(crate) struct PollConfig {
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    pub backoff_factor: f64,
+    pub timeout: Option<Duration>,
+    pub terminal_statuses: Option<Vec<String>>,
+    pub failure_statuses: Option<Vec<String>>,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            initial_interval: DEFAULT_INITIAL_INTERVAL,
+            max_interval: DEFAULT_MAX_INTERVAL,
+            backoff_factor: DEFAULT_BACKOFF_FACTOR,
+            timeout: Some(DEFAULT_TIMEOUT),
+            terminal_statuses: None,
+            failure_statuses: None,
+        }
+    }
+}
+
+impl TryFrom<&FfiPollConfig> for PollConfig {
+    type Error = Error;
+
+    fn try_from(c: &FfiPollConfig) -> Result<Self, Self::Error> {
+        let terminal_statuses = parse_statuses(c.terminal_statuses, c.terminal_statuses_len)?;
+        let failure_statuses = parse_statuses(c.failure_statuses, c.failure_statuses_len)?;
+
+        let initial_interval = match c.initial_interval_ms {
+            0 => DEFAULT_INITIAL_INTERVAL,
+            ms => Duration::from_millis(ms),
+        };
+        let max_interval = match c.max_interval_ms {
+            0 => DEFAULT_MAX_INTERVAL,
+            ms => Duration::from_millis(ms),
+        };
+
+        Ok(Self {
+            initial_interval,
+            max_interval: max_interval.max(initial_interval),
+            backoff_factor: if c.backoff_factor >= 1.0 {
+                c.backoff_factor
+            } else {
+                DEFAULT_BACKOFF_FACTOR
+            },
+            timeout: match c.timeout_ms {
+                0 => Some(DEFAULT_TIMEOUT),
+                u64::MAX => None,
+                ms => Some(Duration::from_millis(ms)),
+            },
+            terminal_statuses,
+            failure_statuses,
+        })
+    }
+}
+
+/// 读取状态字符串数组并转为小写，为 null 时返回 None
+fn parse_statuses(
+    statuses: *const *const c_char,
+    len: usize,
+) -> Result<Option<Vec<String>>, Error> {
+    if statuses.is_null() {
+        return Ok(None);
+    }
+    let statuses = unsafe { std::slice::from_raw_parts(statuses, len) };
+    let statuses = statuses
+        .iter()
+        .map(|&s| parse_c_str(s, |s| Ok(s.to_ascii_lowercase())))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(statuses))
+}
+
+impl PollConfig {
+    /// 从 C 指针读取配置，为 null 时使用默认配置
+    pub fn from_ptr(config: *const FfiPollConfig) -> Result<Self, Error> {
+        match unsafe { read_sized_struct(config, 0)? } {
+            Some(config) => PollConfig::try_from(&config),
+            None => Ok(PollConfig::default()),
+        }
+    }
+
+    /// 状态是否为终止状态
+    pub fn is_terminal(&self, status: &str) -> bool {
+        let status = status.to_ascii_lowercase();
+        match &self.terminal_statuses {
+            Some(statuses) => statuses.contains(&status),
+            None => DEFAULT_TERMINAL_STATUSES.contains(&status.as_str()),
+        }
+    }
+
+    /// 终止状态是否表示任务失败
+    pub fn is_failure(&self, status: &str) -> bool {
+        let status = status.to_ascii_lowercase();
+        match &self.failure_statuses {
+            Some(statuses) => statuses.contains(&status),
+            None => DEFAULT_FAILURE_STATUSES.contains(&status.as_str()),
+        }
+    }
+
+    /// 状态未变化时的下一次轮询间隔
+    pub fn next_interval(&self, interval: Duration) -> Duration {
+        interval.mul_f64(self.backoff_factor).min(self.max_interval)
+    }
+}
+
+/// 获取任务状态，token 失效时刷新后重试一次
+pub(crate) async fn fetch_status(task_id: &str, token: &str) -> Result<TranscribeStatus, Error> {
+    with_token_refresh(token, |token| async move {
+        track(Operation::GetStatus, status(Some(task_id), None, &token)).await
+    })
+    .await
+}
+
+/// 轮询任务状态直到进入终止状态
+///
+/// 状态变化时调用 `on_change`，返回 false 时停止轮询并返回 None。
+/// 可重试的错误（网络错误、限流、5xx 等）只记录日志，等待下一次轮询。
+async fn poll_until_terminal(
+    task_id: &str,
+    token: &str,
+    config: &PollConfig,
+    mut on_change: impl FnMut(&str) -> bool,
+) -> Result<Option<TranscribeStatus>, Error> {
+    let mut interval = config.initial_interval;
+    let mut last_status: Option<String> = None;
+    loop {
+        match fetch_status(task_id, token).await {
+            Ok(s) => {
+                if last_status.as_deref() != Some(s.status.as_str()) {
+                    log::debug!("Task {} status: {}", task_id, s.status);
+                    if !on_change(&s.status) {
+                        return Ok(None);
+                    }
+                    last_status = Some(s.status.clone());
+                    interval = config.initial_interval;
+                } else {
+                    interval = config.next_interval(interval);
+                }
+                if config.is_terminal(&s.status) {
+                    return Ok(Some(s));
+                }
+            }
+            Err(e) if is_retryable(&e) => {
+                log::warn!(
+                    "Failed to poll task {} with {:?}, will retry",
+                    task_id,
+                    error_code(&e)
+                );
+                interval = config.next_interval(interval);
+            }
+            Err(e) => return Err(e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// 任务状态变化回调
+///
+/// 参数依次为任务ID、新状态和 user_data，字符串只在回调期间有效。返回非 0 值将取消等待。
+/// 回调期间本库的同步接口不可用（返回 `InvalidInput` 错误）。
+pub type FfiTaskStatusCallback = Option<
+    extern "C" fn(task_id: *const c_char, status: *const c_char, user_data: *mut c_void) -> c_int,
+>;
+
+/// 上传音频文件并等待转写完成
+///
+/// 上传后按 `poll_config` 轮询任务状态（状态未变化时逐步拉长间隔），直到状态进入终止状态，
+/// 并输出最终状态（包括失败状态，调用方需检查 `status` 字段）。回调在当前线程上调用，
+/// 返回非 0 值时停止等待并返回 `Cancelled` 错误；超过总超时时间（默认 2 小时）返回 `Timeout` 错误。
+/// 一句话转写模式（`short_asr`）的上传直接返回结果，不支持本函数。
+///
+/// # 参数
+/// - `filepath`: 音频文件路径（C 字符串）
+/// - `options`: 上传选项
+/// - `token`: Bearer token（C 字符串）
+/// - `poll_config`: 轮询配置，为 null 时使用默认配置
+/// - `progress_cb`: 状态变化回调（可为 null）
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_status`: 输出最终状态结构体指针，需调用 `transcribe_ffi_free_transcribe_status` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_transcribe_file(
+    filepath: *const c_char,
+    options: *const FfiUploadOptions,
+    token: *const c_char,
+    poll_config: *const FfiPollConfig,
+    progress_cb: FfiTaskStatusCallback,
+    user_data: *mut c_void,
+    out_status: *mut FfiTranscribeStatus,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_status.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
+        let options = unsafe { UploadOptions::read(options)? };
+        if options.short_asr {
+            return Err(Error::InvalidInput(
+                "short_asr uploads return the result directly, use transcribe_ffi_upload"
+                    .to_string(),
+            )
+            .into());
+        }
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let config = PollConfig::from_ptr(poll_config)?;
+        let user_data = UserData::new(user_data);
+
+        let work = async {
+            let response = upload_file(
+                &filepath,
+                options.transcribe_only,
+                options.short_asr,
+                &options.model,
+                &token,
+            )
+            .await?;
+            let task_id = match response {
+                UploadResponse::Normal(normal) => normal.task_id,
+                UploadResponse::OneSentence(_) => {
+                    return Err(Error::InvalidResponse(
+                        "Unexpected one-sentence upload response".to_string(),
+                    ))
+                }
+            };
+            log::info!("Uploaded {} as task {}", filepath, task_id);
+
+            let task_id_c = CString::new(task_id.as_str()).unwrap_or_default();
+            poll_until_terminal(&task_id, &token, &config, |status| {
+                let Some(progress_cb) = progress_cb else {
+                    return true;
+                };
+                let status = CString::new(status).unwrap_or_default();
+                progress_cb(task_id_c.as_ptr(), status.as_ptr(), user_data.get()) == 0
+            })
+            .await
+        };
+
+        let result = block_on(async {
+            match config.timeout {
+                Some(timeout) => metrics::timeout(timeout, work)
+                    .await
+                    .map_err(|_| FfiError::timeout(timeout)),
+                None => Ok(work.await),
+            }
+        })?;
+        let status = result?.ok_or_else(FfiError::cancelled)?;
+
+        let ffi_status = FfiTranscribeStatus::try_from(status)?;
+        unsafe {
+            *out_status = ffi_status;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 初始化状态轮询配置
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `config`: 轮询配置结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_poll_config_init(config: *mut FfiPollConfig) {
+    if config.is_null() {
+        return;
+    }
+
+    unsafe {
+        *config = FfiPollConfig {
+            struct_size: std::mem::size_of::<FfiPollConfig>(),
+            initial_interval_ms: 0,
+            max_interval_ms: 0,
+            backoff_factor: 0.0,
+            timeout_ms: 0,
+            terminal_statuses: std::ptr::null(),
+            terminal_statuses_len: 0,
+            failure_statuses: std::ptr::null(),
+            failure_statuses_len: 0,
+        };
+    }
+}
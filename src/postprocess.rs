@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::ffi::*;
+
+use common::Error;
+use transcribe::types::Utterance;
+
+use crate::{
+    error::FfiError, subtitle::utterances_from_raw, types_ffi::free_c_string, utils::*,
+    FfiUtterance,
+};
+
+/// 句末标点，拆分时优先在其后断开
+const SENTENCE_END: &[char] = &['。', '！', '？', '；', '…', '.', '!', '?', ';'];
+
+/// 句中标点，句子仍然过长时在其后断开
+const CLAUSE_END: &[char] = &['，', '、', '：', ',', ':'];
+
+/// 说话人编号与名称的对应
+#[repr(C)]
+pub struct FfiSpeakerName {
+    pub speaker: i32,
+    /// 说话人名称（C 字符串）
+    pub name: *const c_char,
+}
+
+/// utterance 后处理选项，所有字段为 0 / null 时不做任何处理
+///
+/// 调用方须先用 `transcribe_ffi_utterance_process_options_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiUtteranceProcessOptions)` 并将其余字段清零）。新字段只会追加在末尾，
+/// 未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiUtteranceProcessOptions {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 是否合并同一说话人的相邻 utterance
+    pub merge: bool,
+    /// 合并时允许的最大间隔（毫秒），即前一句结束到后一句开始的时间
+    pub merge_gap_ms: u64,
+    /// 每条 utterance 的最大字符数，超出时在标点处拆分并按字数插值时间，0 表示不拆分
+    pub max_chars: usize,
+    /// 说话人名称数组（可为 null），未列出的说话人名称为 null，同一说话人不能重复出现
+    pub speaker_names: *const FfiSpeakerName,
+    /// 说话人名称数量
+    pub speaker_names_len: usize,
+}
+
+/// 后处理后的 utterance
+#[repr(C)]
+pub struct FfiProcessedUtterance {
+    pub utterance: FfiUtterance,
+    /// 说话人名称（C 字符串），未提供对应名称时为 null
+    pub speaker_name: *mut c_char,
+}
+
+/// 后处理结果
+#[repr(C)]
+pub struct FfiProcessedUtterances {
+    pub items: *mut FfiProcessedUtterance,
+    pub items_len: usize,
+}
+
+/// 解析后的后处理选项
+#[derive(Default)]
+struct ProcessOptions {
+    /// 合并允许的最大间隔（秒），None 表示不合并
+    merge_gap: Option<f64>,
+    max_chars: usize,
+    speaker_names: HashMap<i32, String>,
+}
+
+impl TryFrom<&FfiUtteranceProcessOptions> for ProcessOptions {
+    type Error = Error;
+
+    fn try_from(o: &FfiUtteranceProcessOptions) -> Result<Self, Self::Error> {
+        let names = if o.speaker_names.is_null() || o.speaker_names_len == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(o.speaker_names, o.speaker_names_len) }
+        };
+        let mut speaker_names = HashMap::with_capacity(names.len());
+        for n in names {
+            let name = parse_c_str(n.name, |s| Ok(s.to_string()))?;
+            if speaker_names.insert(n.speaker, name).is_some() {
+                return Err(Error::InvalidInput(format!(
+                    "Duplicate speaker name for speaker {}",
+                    n.speaker
+                )));
+            }
+        }
+
+        Ok(Self {
+            merge_gap: o.merge.then(|| o.merge_gap_ms as f64 / 1000.0),
+            max_chars: o.max_chars,
+            speaker_names,
+        })
+    }
+}
+
+/// 拼接两段文本，两侧都是 ASCII 字符时以空格分隔
+fn join_text(a: &str, b: &str) -> String {
+    let (a, b) = (a.trim_end(), b.trim_start());
+    match (a.chars().last(), b.chars().next()) {
+        (Some(x), Some(y)) if x.is_ascii() && y.is_ascii() => format!("{} {}", a, b),
+        _ => format!("{}{}", a, b),
+    }
+}
+
+/// 合并同一说话人间隔不超过 `gap` 的相邻 utterance
+fn merge(utterances: Vec<Utterance>, gap: f64) -> Vec<Utterance> {
+    let mut merged: Vec<Utterance> = Vec::with_capacity(utterances.len());
+    for u in utterances {
+        match merged.last_mut() {
+            Some(last) if last.speaker == u.speaker && u.start_time - last.end_time <= gap => {
+                last.text = join_text(&last.text, &u.text);
+                last.end_time = last.end_time.max(u.end_time);
+            }
+            _ => merged.push(u),
+        }
+    }
+    merged
+}
+
+/// 在标点后断开文本，连续的标点不拆开；ASCII 标点后需跟空白，以免拆开小数和缩写
+fn split_after<'a>(text: &'a str, breaks: &[char]) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !breaks.contains(&c) {
+            continue;
+        }
+        let next = chars.peek().map(|&(_, n)| n);
+        let at_boundary = match next {
+            None => true,
+            Some(n) if breaks.contains(&n) => false,
+            Some(n) => !c.is_ascii() || n.is_whitespace(),
+        };
+        if at_boundary {
+            let end = i + c.len_utf8();
+            parts.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        parts.push(&text[start..]);
+    }
+    parts
+}
+
+/// 将过长的文本拆分为不超过 `max` 个字符的片段（单个句子本身过长时保持原样）
+fn split_text(text: &str, max: usize) -> Vec<String> {
+    let count = |s: &str| s.trim().chars().count();
+    let pieces: Vec<&str> = split_after(text, SENTENCE_END)
+        .into_iter()
+        .flat_map(|sentence| {
+            if count(sentence) > max {
+                split_after(sentence, CLAUSE_END)
+            } else {
+                vec![sentence]
+            }
+        })
+        .collect();
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for piece in pieces {
+        if !current.trim().is_empty() && count(&current) + count(piece) > max {
+            chunks.push(current.trim().to_string());
+            current.clear();
+        }
+        current.push_str(piece);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+    chunks
+}
+
+/// 拆分过长的 utterance，时间按字数比例插值
+fn split(utterances: Vec<Utterance>, max: usize) -> Vec<Utterance> {
+    let mut result = Vec::with_capacity(utterances.len());
+    for u in utterances {
+        if u.text.trim().chars().count() <= max {
+            result.push(u);
+            continue;
+        }
+
+        let chunks = split_text(&u.text, max);
+        let total: usize = chunks.iter().map(|c| c.chars().count()).sum();
+        let duration = (u.end_time - u.start_time).max(0.0);
+        let mut start = u.start_time;
+        for (i, text) in chunks.iter().enumerate() {
+            let end = if i + 1 == chunks.len() {
+                u.end_time
+            } else {
+                start + duration * text.chars().count() as f64 / total.max(1) as f64
+            };
+            result.push(Utterance {
+                start_time: start,
+                end_time: end,
+                speaker: u.speaker,
+                text: text.clone(),
+            });
+            start = end;
+        }
+    }
+    result
+}
+
+fn process(mut utterances: Vec<Utterance>, options: &ProcessOptions) -> Vec<Utterance> {
+    if let Some(gap) = options.merge_gap {
+        utterances = merge(utterances, gap);
+    }
+    if options.max_chars > 0 {
+        utterances = split(utterances, options.max_chars);
+    }
+    utterances
+}
+
+/// 对 utterance 数组做后处理
+///
+/// 依次合并同一说话人的相邻 utterance、在标点处拆分过长的 utterance，并为说话人标注名称。
+/// 在本地处理，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+/// `details` / `details_len`，时间单位为秒，按原顺序处理。
+///
+/// # 参数
+/// - `utterances`: utterance 数组指针
+/// - `utterances_len`: utterance 数量
+/// - `options`: 后处理选项，为 null 时原样复制
+/// - `out_result`: 输出结果结构体指针，需调用 `transcribe_ffi_free_processed_utterances` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_process_utterances(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    options: *const FfiUtteranceProcessOptions,
+    out_result: *mut FfiProcessedUtterances,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+        let utterances = utterances_from_raw(utterances, utterances_len)?;
+        let options = match unsafe { read_sized_struct(options, 0)? } {
+            Some(options) => ProcessOptions::try_from(&options)?,
+            None => ProcessOptions::default(),
+        };
+
+        let utterances = process(utterances, &options);
+        let mut items = Vec::with_capacity(utterances.len());
+        for u in utterances {
+            let speaker = u.speaker;
+            let utterance = match FfiUtterance::try_from(u) {
+                Ok(utterance) => utterance,
+                Err(e) => {
+                    for item in items.iter_mut() {
+                        unsafe { free_processed_utterance(item) };
+                    }
+                    return Err(e);
+                }
+            };
+            // 名称来自 C 字符串，不含 NUL
+            let speaker_name = options
+                .speaker_names
+                .get(&speaker)
+                .and_then(|name| CString::new(name.as_str()).ok())
+                .map(CString::into_raw)
+                .unwrap_or(std::ptr::null_mut());
+            items.push(FfiProcessedUtterance {
+                utterance,
+                speaker_name,
+            });
+        }
+
+        let items_len = items.len();
+        let items = if items_len > 0 {
+            Box::into_raw(items.into_boxed_slice()) as *mut FfiProcessedUtterance
+        } else {
+            std::ptr::null_mut()
+        };
+        unsafe {
+            *out_result = FfiProcessedUtterances { items, items_len };
+        }
+
+        Ok(())
+    })
+}
+
+/// 释放后处理结果中的字符串
+unsafe fn free_processed_utterance(item: &mut FfiProcessedUtterance) {
+    free_c_string(&mut item.utterance.text);
+    free_c_string(&mut item.speaker_name);
+}
+
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_processed_utterances(s: *mut FfiProcessedUtterances) {
+    if s.is_null() {
+        return;
+    }
+    unsafe {
+        let s = &mut *s;
+        if !s.items.is_null() && s.items_len > 0 {
+            let mut items = Box::from_raw(std::ptr::slice_from_raw_parts_mut(s.items, s.items_len));
+            for item in items.iter_mut() {
+                free_processed_utterance(item);
+            }
+        }
+        s.items = std::ptr::null_mut();
+        s.items_len = 0;
+    }
+}
+
+/// 初始化 utterance 后处理选项
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `options`: 后处理选项结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_utterance_process_options_init(
+    options: *mut FfiUtteranceProcessOptions,
+) {
+    if options.is_null() {
+        return;
+    }
+
+    unsafe {
+        *options = FfiUtteranceProcessOptions {
+            struct_size: std::mem::size_of::<FfiUtteranceProcessOptions>(),
+            merge: false,
+            merge_gap_ms: 0,
+            max_chars: 0,
+            speaker_names: std::ptr::null(),
+            speaker_names_len: 0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(start: f64, end: f64, speaker: i32, text: &str) -> Utterance {
+        Utterance {
+            start_time: start,
+            end_time: end,
+            speaker,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn split_after_keeps_decimals_and_runs_of_punctuation() {
+        assert_eq!(
+            split_after("It costs 3.5 dollars. Really?! Yes", SENTENCE_END),
+            ["It costs 3.5 dollars.", " Really?!", " Yes"]
+        );
+        assert_eq!(
+            split_after("你好。今天怎么样？", SENTENCE_END),
+            ["你好。", "今天怎么样？"]
+        );
+        assert_eq!(split_after("e.g.done", SENTENCE_END), ["e.g.done"]);
+    }
+
+    #[test]
+    fn split_text_packs_sentences_and_clauses() {
+        assert_eq!(
+            split_text("一二三。四五六。七八。", 8),
+            ["一二三。四五六。", "七八。"]
+        );
+        assert_eq!(
+            split_text("一二三，四五六，七八九。", 4),
+            ["一二三，", "四五六，", "七八九。"]
+        );
+        assert_eq!(split_text("一二三四五六七八", 4), ["一二三四五六七八"]);
+    }
+
+    #[test]
+    fn merge_joins_same_speaker_within_gap() {
+        let merged = merge(
+            vec![
+                utterance(0.0, 1.0, 0, "Hello"),
+                utterance(1.2, 2.0, 0, "world"),
+                utterance(2.1, 3.0, 1, "你好"),
+                utterance(3.0, 4.0, 1, "世界"),
+                utterance(9.0, 10.0, 1, "later"),
+            ],
+            0.5,
+        );
+        let merged: Vec<_> = merged
+            .iter()
+            .map(|u| (u.start_time, u.end_time, u.speaker, u.text.as_str()))
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                (0.0, 2.0, 0, "Hello world"),
+                (2.1, 4.0, 1, "你好世界"),
+                (9.0, 10.0, 1, "later"),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_interpolates_times() {
+        let split = split(vec![utterance(0.0, 6.0, 0, "一二。三四五六。")], 4);
+        let times: Vec<_> = split.iter().map(|u| (u.start_time, u.end_time)).collect();
+        assert_eq!(times, [(0.0, 2.25), (2.25, 6.0)]);
+    }
+
+    #[test]
+    fn rejects_duplicate_speaker_names() {
+        let names = [
+            FfiSpeakerName {
+                speaker: 1,
+                name: c"A".as_ptr(),
+            },
+            FfiSpeakerName {
+                speaker: 1,
+                name: c"B".as_ptr(),
+            },
+        ];
+        let mut options = FfiUtteranceProcessOptions {
+            struct_size: 0,
+            merge: false,
+            merge_gap_ms: 0,
+            max_chars: 0,
+            speaker_names: names.as_ptr(),
+            speaker_names_len: names.len(),
+        };
+        assert!(matches!(
+            ProcessOptions::try_from(&options),
+            Err(Error::InvalidInput(_))
+        ));
+        options.speaker_names_len = 1;
+        assert!(ProcessOptions::try_from(&options).is_ok());
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::hash_map::RandomState;
+use std::ffi::*;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+
+use common::Error;
+
+use crate::error::{ErrorCode, FfiError};
+use crate::runtime::block_on;
+use crate::token::with_token_refresh;
+use crate::utils::{ffi_execute, read_sized_struct};
+
+/// 全局重试策略，作用于不使用客户端句柄的幂等请求
+static RETRY_POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::NONE);
+
+/// 重试策略（仅作用于幂等请求）
+#[derive(Clone, Debug)]
+pub(crate) struct RetryPolicy {
+    /// 最大尝试次数（包括首次请求），0 和 1 都表示不重试
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub base_delay: Duration,
+    /// 等待时间上限
+    pub max_delay: Duration,
+    /// 是否在 0 到计算出的等待时间之间随机取值
+    pub jitter: bool,
+    /// 可重试的错误码，为 None 时按错误详情中的 `retryable` 判断
+    pub retryable_codes: Option<Vec<ErrorCode>>,
+}
+
+impl RetryPolicy {
+    /// 不重试
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        jitter: false,
+        retryable_codes: None,
+    };
+
+    /// 固定间隔重试
+    pub fn fixed(max_retries: u32, delay: Duration) -> Self {
+        Self {
+            max_attempts: max_retries.saturating_add(1),
+            base_delay: delay,
+            max_delay: delay,
+            ..Self::NONE
+        }
+    }
+
+    /// 全局重试策略
+    pub fn global() -> Self {
+        RETRY_POLICY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn is_retryable(&self, error: &FfiError) -> bool {
+        match &self.retryable_codes {
+            Some(codes) => codes.contains(&error.code),
+            None => error.details().is_some_and(|d| d.retryable),
+        }
+    }
+
+    /// 第 `attempt` 次失败后的等待时间
+    fn delay(&self, attempt: u32, error: &FfiError) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX));
+        let max_delay = self.max_delay.max(self.base_delay);
+        let mut delay = exp.min(max_delay);
+        if self.jitter && !delay.is_zero() {
+            let random = RandomState::new().build_hasher().finish();
+            delay = Duration::from_nanos(random % (delay.as_nanos() as u64 + 1));
+        }
+
+        // 服务端要求的等待时间同样受上限约束
+        let retry_after = Duration::from_millis(error.details().map_or(0, |d| d.retry_after_ms));
+        delay.max(retry_after.min(max_delay))
+    }
+
+    /// 从 C 指针读取重试策略，为 null 时不重试
+    pub(crate) fn from_ptr(policy: *const FfiRetryPolicy) -> Result<Self, Error> {
+        match unsafe { read_sized_struct(policy, 0)? } {
+            Some(policy) => RetryPolicy::try_from(&policy),
+            None => Ok(RetryPolicy::NONE),
+        }
+    }
+}
+
+/// 按策略执行请求，可重试的错误会在等待后重新调用 `f`
+///
+/// 最终返回的错误详情中会记录尝试次数
+pub(crate) async fn with_retry<T, E, F, Fut>(policy: &RetryPolicy, mut f: F) -> Result<T, FfiError>
+where
+    E: Into<FfiError>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        // 等待前先释放本次的错误信息
+        let delay = {
+            let mut error: FfiError = match f().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e.into(),
+            };
+
+            if attempt >= policy.max_attempts || !policy.is_retryable(&error) {
+                error.details_mut().attempts = attempt;
+                return Err(error);
+            }
+
+            let delay = policy.delay(attempt, &error);
+            log::warn!(
+                "Request failed with {:?}, retrying in {} ms ({}/{})",
+                error.code,
+                delay.as_millis(),
+                attempt,
+                policy.max_attempts - 1
+            );
+            crate::transcribe_ffi_free_error(&mut error);
+            delay
+        };
+
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// 在全局 runtime 上同步执行幂等请求，按全局重试策略重试
+///
+/// `f` 以 token 为参数构造请求 future，token 失效时会刷新 token 后重新调用
+pub(crate) fn block_on_idempotent<T, F, Fut>(token: &str, f: F) -> Result<T, FfiError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    block_on(with_retry(&RetryPolicy::global(), || {
+        with_token_refresh(token, &f)
+    }))
+}
+
+/// 重试策略配置
+///
+/// 调用方须先用 `transcribe_ffi_retry_policy_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiRetryPolicy)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按 0 处理。
+#[repr(C)]
+pub struct FfiRetryPolicy {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 最大尝试次数（包括首次请求），0 和 1 都表示不重试
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    pub base_delay_ms: u64,
+    /// 等待时间上限（毫秒），服务端返回的 Retry-After 也不会超过该值
+    pub max_delay_ms: u64,
+    /// 是否在 0 到计算出的等待时间之间随机取值
+    pub jitter: bool,
+    /// 可重试的错误码数组，为 null 时重试网络错误、超时、限流和 5xx 错误
+    pub retryable_codes: *const c_int,
+    /// 可重试的错误码数量
+    pub retryable_codes_len: usize,
+}
+
+impl TryFrom<&FfiRetryPolicy> for RetryPolicy {
+    type Error = Error;
+
+    fn try_from(p: &FfiRetryPolicy) -> Result<Self, Self::Error> {
+        let retryable_codes = if p.retryable_codes.is_null() {
+            None
+        } else {
+            let codes =
+                unsafe { std::slice::from_raw_parts(p.retryable_codes, p.retryable_codes_len) };
+            let codes = codes
+                .iter()
+                .map(|&c| {
+                    ErrorCode::try_from(c)
+                        .map_err(|c| Error::InvalidInput(format!("Invalid error code: {}", c)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(codes)
+        };
+
+        Ok(Self {
+            max_attempts: p.max_attempts.max(1),
+            base_delay: Duration::from_millis(p.base_delay_ms),
+            max_delay: Duration::from_millis(p.max_delay_ms),
+            jitter: p.jitter,
+            retryable_codes,
+        })
+    }
+}
+
+/// 设置全局重试策略
+///
+/// 作用于不使用客户端句柄的幂等请求：获取状态、导出、获取分享链接和获取翻译结果
+/// （包括对应的异步接口）。默认不重试。
+///
+/// # 参数
+/// - `policy`: 重试策略，为 null 时恢复为不重试
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_retry_policy(
+    policy: *const FfiRetryPolicy,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let policy = RetryPolicy::from_ptr(policy)?;
+        *RETRY_POLICY.write().unwrap_or_else(PoisonError::into_inner) = policy;
+
+        Ok::<(), Error>(())
+    })
+}
+
+/// 初始化重试策略配置
+///
+/// 将所有字段清零（不重试），并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `policy`: 重试策略结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_retry_policy_init(policy: *mut FfiRetryPolicy) {
+    if policy.is_null() {
+        return;
+    }
+
+    unsafe {
+        *policy = FfiRetryPolicy {
+            struct_size: std::mem::size_of::<FfiRetryPolicy>(),
+            max_attempts: 0,
+            base_delay_ms: 0,
+            max_delay_ms: 0,
+            jitter: false,
+            retryable_codes: std::ptr::null(),
+            retryable_codes_len: 0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_with_retry_after(retry_after_ms: u64) -> FfiError {
+        let mut error = FfiError::timeout(Duration::from_secs(1));
+        error.details_mut().retry_after_ms = retry_after_ms;
+        error
+    }
+
+    fn policy(base_ms: u64, max_ms: u64, jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(base_ms),
+            max_delay: Duration::from_millis(max_ms),
+            jitter,
+            retryable_codes: None,
+        }
+    }
+
+    #[test]
+    fn delay_doubles_up_to_cap() {
+        let policy = policy(100, 500, false);
+        let mut error = error_with_retry_after(0);
+        let delays: Vec<u128> = (1..=5)
+            .map(|attempt| policy.delay(attempt, &error).as_millis())
+            .collect();
+        assert_eq!(delays, [100, 200, 400, 500, 500]);
+        crate::transcribe_ffi_free_error(&mut error);
+    }
+
+    #[test]
+    fn delay_survives_shift_overflow() {
+        let policy = policy(100, 500, false);
+        let mut error = error_with_retry_after(0);
+        assert_eq!(policy.delay(33, &error), Duration::from_millis(500));
+        assert_eq!(policy.delay(u32::MAX, &error), Duration::from_millis(500));
+        crate::transcribe_ffi_free_error(&mut error);
+    }
+
+    #[test]
+    fn delay_jitter_stays_within_bounds() {
+        let policy = policy(100, 500, true);
+        let mut error = error_with_retry_after(0);
+        for attempt in 1..=5 {
+            let cap = policy
+                .base_delay
+                .saturating_mul(1 << (attempt - 1))
+                .min(policy.max_delay);
+            for _ in 0..50 {
+                assert!(policy.delay(attempt, &error) <= cap);
+            }
+        }
+        crate::transcribe_ffi_free_error(&mut error);
+    }
+
+    #[test]
+    fn retry_after_is_honoured_but_capped() {
+        let policy = policy(100, 500, false);
+
+        let mut error = error_with_retry_after(300);
+        assert_eq!(policy.delay(1, &error), Duration::from_millis(300));
+        crate::transcribe_ffi_free_error(&mut error);
+
+        let mut error = error_with_retry_after(60_000);
+        assert_eq!(policy.delay(1, &error), Duration::from_millis(500));
+        crate::transcribe_ffi_free_error(&mut error);
+    }
+
+    #[test]
+    fn is_retryable_uses_details_or_codes() {
+        let mut timeout = FfiError::timeout(Duration::from_secs(1));
+        let mut cancelled = FfiError::cancelled();
+
+        let by_details = policy(100, 500, false);
+        assert!(by_details.is_retryable(&timeout));
+        assert!(!by_details.is_retryable(&cancelled));
+
+        let by_codes = RetryPolicy {
+            retryable_codes: Some(vec![ErrorCode::Cancelled]),
+            ..by_details
+        };
+        assert!(!by_codes.is_retryable(&timeout));
+        assert!(by_codes.is_retryable(&cancelled));
+
+        crate::transcribe_ffi_free_error(&mut timeout);
+        crate::transcribe_ffi_free_error(&mut cancelled);
+    }
+}
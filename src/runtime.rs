@@ -1,16 +1,375 @@
-use std::sync::OnceLock;
+use std::ffi::*;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use common::Error;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::sync::oneshot;
+
+use crate::{error::FfiError, transcribe_stream::close_all_streams, utils::*};
+
+/// 默认工作线程数
+const DEFAULT_WORKER_THREADS: usize = 4;
+
+/// 默认线程名前缀
+const DEFAULT_THREAD_NAME: &str = "dianyaapi-ffi";
 
 /// 全局 Tokio runtime，用于执行异步操作
-/// 使用 multi_thread runtime 以支持从任何线程调用 block_on
-static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
-
-/// 获取或创建全局 runtime
-pub(crate) fn get_runtime() -> &'static tokio::runtime::Runtime {
-    RUNTIME.get_or_init(|| {
-        tokio::runtime::Builder::new_multi_thread()
-            .worker_threads(4)
+/// 未显式初始化时，首次使用会创建默认的 multi_thread runtime
+static RUNTIME: Mutex<RuntimeSlot> = Mutex::new(RuntimeSlot::Uninit);
+
+/// 正在 runtime 上执行的任务数（包括同步调用）
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// runtime 配置
+///
+/// 调用方须先用 `transcribe_ffi_runtime_config_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiRuntimeConfig)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiRuntimeConfig {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 工作线程数，0 表示使用默认值 4（current_thread 模式下忽略）
+    pub worker_threads: usize,
+    /// 线程名前缀（C 字符串，可为 null，默认 "dianyaapi-ffi"）
+    pub thread_name_prefix: *const c_char,
+    /// 线程栈大小（字节），0 表示使用默认值
+    pub thread_stack_size: usize,
+    /// 是否使用单线程 runtime，任务在一个独立的驱动线程上执行
+    pub current_thread: bool,
+}
+
+struct RuntimeOptions {
+    worker_threads: usize,
+    thread_name_prefix: String,
+    thread_stack_size: usize,
+    current_thread: bool,
+}
+
+impl Default for RuntimeOptions {
+    fn default() -> Self {
+        Self {
+            worker_threads: DEFAULT_WORKER_THREADS,
+            thread_name_prefix: DEFAULT_THREAD_NAME.to_string(),
+            thread_stack_size: 0,
+            current_thread: false,
+        }
+    }
+}
+
+impl TryFrom<&FfiRuntimeConfig> for RuntimeOptions {
+    type Error = Error;
+
+    fn try_from(config: &FfiRuntimeConfig) -> Result<Self, Self::Error> {
+        let thread_name_prefix = if config.thread_name_prefix.is_null() {
+            DEFAULT_THREAD_NAME.to_string()
+        } else {
+            parse_c_str(config.thread_name_prefix, |s| Ok(s.to_string()))?
+        };
+
+        Ok(Self {
+            worker_threads: match config.worker_threads {
+                0 => DEFAULT_WORKER_THREADS,
+                n => n,
+            },
+            thread_name_prefix,
+            thread_stack_size: config.thread_stack_size,
+            current_thread: config.current_thread,
+        })
+    }
+}
+
+/// current_thread 模式下的驱动线程
+struct Driver {
+    /// 发送关闭信号（附带关闭超时）
+    shutdown: oneshot::Sender<Duration>,
+    thread: JoinHandle<()>,
+}
+
+struct RuntimeState {
+    handle: Handle,
+    /// multi_thread 模式下持有的 runtime
+    runtime: Option<Runtime>,
+    /// current_thread 模式下持有 runtime 的驱动线程
+    driver: Option<Driver>,
+}
+
+impl RuntimeState {
+    fn build(options: RuntimeOptions) -> std::io::Result<Self> {
+        let mut builder = if options.current_thread {
+            Builder::new_current_thread()
+        } else {
+            let mut builder = Builder::new_multi_thread();
+            builder.worker_threads(options.worker_threads);
+            builder
+        };
+
+        let prefix = options.thread_name_prefix.clone();
+        let next_id = Arc::new(AtomicUsize::new(0));
+        builder
             .enable_all()
-            .build()
-            .expect("Failed to create Tokio runtime")
+            .thread_name_fn(move || {
+                format!("{}-{}", prefix, next_id.fetch_add(1, Ordering::Relaxed))
+            })
+            .on_thread_start(|| log::trace!("Runtime thread started"))
+            .on_thread_stop(|| log::trace!("Runtime thread stopped"));
+        if options.thread_stack_size > 0 {
+            builder.thread_stack_size(options.thread_stack_size);
+        }
+
+        let runtime = builder.build()?;
+        let handle = runtime.handle().clone();
+        log::info!(
+            "Runtime started ({})",
+            if options.current_thread {
+                "current_thread".to_string()
+            } else {
+                format!("multi_thread, {} workers", options.worker_threads)
+            }
+        );
+
+        if !options.current_thread {
+            return Ok(Self {
+                handle,
+                runtime: Some(runtime),
+                driver: None,
+            });
+        }
+
+        // current_thread runtime 只有在 Runtime::block_on 中才会驱动 IO 和定时器，
+        // 因此由一个独立线程持续驱动，其他线程通过 Handle 提交任务
+        let (shutdown, signal) = oneshot::channel::<Duration>();
+        let mut thread =
+            std::thread::Builder::new().name(format!("{}-driver", options.thread_name_prefix));
+        if options.thread_stack_size > 0 {
+            thread = thread.stack_size(options.thread_stack_size);
+        }
+        let thread = thread.spawn(move || {
+            let timeout = runtime.block_on(async { signal.await.unwrap_or_default() });
+            runtime.shutdown_timeout(timeout);
+        })?;
+
+        Ok(Self {
+            handle,
+            runtime: None,
+            driver: Some(Driver { shutdown, thread }),
+        })
+    }
+
+    fn shutdown(self, timeout: Duration) {
+        if let Some(runtime) = self.runtime {
+            runtime.shutdown_timeout(timeout);
+        }
+        if let Some(driver) = self.driver {
+            let _ = driver.shutdown.send(timeout);
+            let _ = driver.thread.join();
+        }
+    }
+}
+
+/// 全局 runtime 的状态
+enum RuntimeSlot {
+    /// 尚未创建，首次使用时创建默认 runtime
+    Uninit,
+    Running(RuntimeState),
+    /// 已调用 `transcribe_ffi_runtime_shutdown`，需重新 `transcribe_ffi_runtime_init` 才能使用
+    ShutDown,
+}
+
+fn lock_runtime() -> MutexGuard<'static, RuntimeSlot> {
+    RUNTIME.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 获取全局 runtime，未初始化时创建默认 runtime
+///
+/// runtime 已关闭（或正在关闭）时返回 `RuntimeShutDown` 错误
+pub(crate) fn get_runtime() -> Result<Handle, FfiError> {
+    let mut slot = lock_runtime();
+    match &*slot {
+        RuntimeSlot::Running(state) => Ok(state.handle.clone()),
+        RuntimeSlot::ShutDown => Err(FfiError::runtime_shut_down()),
+        RuntimeSlot::Uninit => {
+            let state = RuntimeState::build(RuntimeOptions::default())
+                .map_err(|e| Error::OtherError(format!("Failed to create Tokio runtime: {}", e)))?;
+            let handle = state.handle.clone();
+            *slot = RuntimeSlot::Running(state);
+            Ok(handle)
+        }
+    }
+}
+
+/// 计入 `IN_FLIGHT` 的任务，释放时减少计数
+struct InFlight;
+
+impl InFlight {
+    fn enter() -> Self {
+        IN_FLIGHT.fetch_add(1, Ordering::AcqRel);
+        InFlight
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// 在全局 runtime 上同步执行请求，并计入关闭时需要等待的任务
+///
+/// 在 runtime 线程上（例如异步接口的回调中）调用时返回 `InvalidInput` 错误，
+/// 而不是让 Tokio 在嵌套的 block_on 中 panic
+pub(crate) fn block_on<T, E, F>(fut: F) -> Result<T, FfiError>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<FfiError>,
+{
+    if Handle::try_current().is_ok() {
+        return Err(Error::InvalidInput(
+            "Blocking calls are not allowed inside callbacks, use the async API instead"
+                .to_string(),
+        )
+        .into());
+    }
+    let runtime = get_runtime()?;
+    let _in_flight = InFlight::enter();
+    runtime.block_on(fut).map_err(Into::into)
+}
+
+/// 在 runtime 上启动任务，并计入关闭时需要等待的任务
+///
+/// `runtime` 应在构造 `fut` 之前通过 `get_runtime` 获取，以免 runtime 已关闭时
+/// `fut` 被直接丢弃
+pub(crate) fn spawn<F>(runtime: &Handle, fut: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let in_flight = InFlight::enter();
+    runtime.spawn(async move {
+        let _in_flight = in_flight;
+        fut.await
+    })
+}
+
+/// 初始化全局 runtime
+///
+/// 必须在其他调用之前（或 `transcribe_ffi_runtime_shutdown` 之后）调用，
+/// runtime 已存在时返回错误。未调用时首次使用会创建 4 个工作线程的默认 runtime。
+/// 关闭后只能通过本函数重新创建 runtime。
+///
+/// # 参数
+/// - `config`: runtime 配置，为 null 时使用默认配置
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_runtime_init(
+    config: *const FfiRuntimeConfig,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let options = match unsafe { read_sized_struct(config, 0)? } {
+            Some(config) => RuntimeOptions::try_from(&config)?,
+            None => RuntimeOptions::default(),
+        };
+
+        let mut slot = lock_runtime();
+        if matches!(*slot, RuntimeSlot::Running(_)) {
+            return Err(Error::InvalidInput(
+                "Runtime already initialized".to_string(),
+            ));
+        }
+        let runtime = RuntimeState::build(options)
+            .map_err(|e| Error::OtherError(format!("Failed to create Tokio runtime: {}", e)))?;
+        *slot = RuntimeSlot::Running(runtime);
+
+        Ok(())
+    })
+}
+
+/// 关闭全局 runtime
+///
+/// 先等待正在执行的异步操作和其他线程上的同步调用结束，超时后取消剩余异步操作
+/// （其回调以 `Cancelled` 错误调用，操作句柄进入 `Cancelled` 状态），仍未返回的同步调用
+/// 以 `RuntimeShutDown` 或网络错误返回；然后关闭所有未释放的 WebSocket 连接。
+/// 任务监视器不计入等待的操作，runtime 关闭后直接停止。
+/// 不能在本库的回调中调用。
+///
+/// 从开始关闭起，需要 runtime 的接口（网络请求、异步操作、WebSocket、任务监视器）
+/// 都返回 `RuntimeShutDown` 错误，直到再次调用 `transcribe_ffi_runtime_init`。
+/// 不需要 runtime 的接口仍可正常调用：各 `free` 函数、操作句柄的查询 / 等待 / 取消 /
+/// 取结果、错误信息、日志、指标、重试策略、token 提供者以及本地渲染和统计。
+/// runtime 未创建或已关闭时再次调用本函数直接返回成功。
+///
+/// # 参数
+/// - `timeout_ms`: 等待正在执行的操作的超时时间（毫秒），0 表示立即取消
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_runtime_shutdown(
+    timeout_ms: u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if Handle::try_current().is_ok() {
+            return Err(Error::InvalidInput(
+                "Cannot shut down the runtime from one of its threads".to_string(),
+            ));
+        }
+
+        // runtime 从未创建时保持 Uninit，之后仍可按需创建默认 runtime
+        let state = {
+            let mut slot = lock_runtime();
+            if !matches!(*slot, RuntimeSlot::Running(_)) {
+                return Ok(());
+            }
+            match std::mem::replace(&mut *slot, RuntimeSlot::ShutDown) {
+                RuntimeSlot::Running(state) => state,
+                RuntimeSlot::Uninit | RuntimeSlot::ShutDown => unreachable!(),
+            }
+        };
+
+        let timeout = Duration::from_millis(timeout_ms);
+        let deadline = Instant::now() + timeout;
+        while IN_FLIGHT.load(Ordering::Acquire) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let pending = IN_FLIGHT.load(Ordering::Acquire);
+        if pending > 0 {
+            log::warn!("Cancelling {} in-flight operations on shutdown", pending);
+        }
+        state.shutdown(deadline.saturating_duration_since(Instant::now()));
+        close_all_streams();
+        log::info!("Runtime shut down");
+
+        Ok(())
     })
 }
+
+/// 初始化 runtime 配置
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `config`: runtime 配置结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_runtime_config_init(config: *mut FfiRuntimeConfig) {
+    if config.is_null() {
+        return;
+    }
+
+    unsafe {
+        *config = FfiRuntimeConfig {
+            struct_size: std::mem::size_of::<FfiRuntimeConfig>(),
+            worker_threads: 0,
+            thread_name_prefix: std::ptr::null(),
+            thread_stack_size: 0,
+            current_thread: false,
+        };
+    }
+}
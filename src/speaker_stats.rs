@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::ffi::*;
+
+use common::Error;
+use transcribe::types::Utterance;
+
+use crate::{error::FfiError, subtitle::utterances_from_raw, utils::*, FfiUtterance};
+
+/// 单个说话人的统计
+#[repr(C)]
+pub struct FfiSpeakerStat {
+    /// 说话人编号
+    pub speaker: i32,
+    /// 发言总时长（秒）
+    pub talk_time: f64,
+    /// 发言时长占所有已识别说话人发言总时长的比例（0 ~ 1）
+    pub talk_ratio: f64,
+    /// 发言轮次（连续的同一说话人 utterance 计为一轮）
+    pub turns: usize,
+    /// 打断次数（在其他说话人的发言结束前开始发言）
+    pub interruptions: usize,
+    /// 最长的一轮连续发言（秒）
+    pub longest_monologue: f64,
+    /// 词数（中日韩文字按字计数，其他文字按空白和标点分词）
+    pub words: usize,
+    /// 每分钟词数（按发言时长计算）
+    pub words_per_minute: f64,
+}
+
+/// 说话人统计结果
+#[repr(C)]
+pub struct FfiSpeakerStats {
+    /// 各说话人的统计，按说话人编号升序排列，不包括未识别的说话人（编号为负）
+    pub speakers: *mut FfiSpeakerStat,
+    pub speakers_len: usize,
+    /// 从第一句开始到最后一句结束的总时长（秒）
+    pub total_time: f64,
+    /// 其间无人发言的总时长（秒）
+    pub silence_time: f64,
+    /// 其间两个及以上已识别的说话人同时发言的总时长（秒）
+    pub overlap_time: f64,
+}
+
+/// 是否为按字计数的文字（中日韩文字、假名、谚文）
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FFFF}')
+}
+
+/// 统计词数
+fn count_words(text: &str) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            words += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || (in_word && (c == '\'' || c == '-')) {
+            if !in_word {
+                words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+    words
+}
+
+/// 计算说话人统计
+///
+/// 未识别的说话人（编号为负）不单独统计，也不参与轮次、打断和重叠的计算，
+/// 但其发言时段不计入静默
+fn compute(mut utterances: Vec<Utterance>) -> (Vec<FfiSpeakerStat>, f64, f64, f64) {
+    utterances.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let mut stats: BTreeMap<i32, FfiSpeakerStat> = BTreeMap::new();
+    // 各说话人最近一次发言的结束时间
+    let mut last_end: BTreeMap<i32, f64> = BTreeMap::new();
+    // 当前轮次的说话人和开始时间
+    let mut turn: Option<(i32, f64)> = None;
+
+    for u in utterances.iter().filter(|u| u.speaker >= 0) {
+        let duration = (u.end_time - u.start_time).max(0.0);
+        let interrupted = last_end
+            .iter()
+            .any(|(&speaker, &end)| speaker != u.speaker && end > u.start_time);
+
+        let stat = stats.entry(u.speaker).or_insert(FfiSpeakerStat {
+            speaker: u.speaker,
+            talk_time: 0.0,
+            talk_ratio: 0.0,
+            turns: 0,
+            interruptions: 0,
+            longest_monologue: 0.0,
+            words: 0,
+            words_per_minute: 0.0,
+        });
+        stat.talk_time += duration;
+        stat.words += count_words(&u.text);
+        if interrupted {
+            stat.interruptions += 1;
+        }
+
+        let turn_start = match turn {
+            Some((speaker, start)) if speaker == u.speaker => start,
+            _ => {
+                stat.turns += 1;
+                u.start_time
+            }
+        };
+        turn = Some((u.speaker, turn_start));
+        stat.longest_monologue = stat.longest_monologue.max(u.end_time - turn_start);
+
+        let end = last_end.entry(u.speaker).or_insert(u.end_time);
+        *end = end.max(u.end_time);
+    }
+
+    let total_talk: f64 = stats.values().map(|s| s.talk_time).sum();
+    for stat in stats.values_mut() {
+        if total_talk > 0.0 {
+            stat.talk_ratio = stat.talk_time / total_talk;
+        }
+        if stat.talk_time > 0.0 {
+            stat.words_per_minute = stat.words as f64 * 60.0 / stat.talk_time;
+        }
+    }
+
+    // 扫描线统计静默和重叠时长，同一时刻先处理结束再处理开始。
+    // 重叠按同时发言的不同说话人计算，同一说话人的 utterance 相互重叠不计入
+    let mut events: Vec<(f64, i32, i32)> = utterances
+        .iter()
+        .filter(|u| u.end_time > u.start_time)
+        .flat_map(|u| [(u.start_time, 1, u.speaker), (u.end_time, -1, u.speaker)])
+        .collect();
+    events.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let (mut silence, mut overlap) = (0.0, 0.0);
+    // 各说话人正在进行的 utterance 数
+    let mut active: BTreeMap<i32, i32> = BTreeMap::new();
+    for pair in events.windows(2) {
+        let (_, delta, speaker) = pair[0];
+        let count = active.entry(speaker).or_insert(0);
+        *count += delta;
+        if *count == 0 {
+            active.remove(&speaker);
+        }
+
+        let span = pair[1].0 - pair[0].0;
+        let speakers = active.keys().filter(|&&s| s >= 0).count();
+        if active.is_empty() {
+            silence += span;
+        } else if speakers >= 2 {
+            overlap += span;
+        }
+    }
+    let total = match (events.first(), events.last()) {
+        (Some(first), Some(last)) => last.0 - first.0,
+        _ => 0.0,
+    };
+
+    (stats.into_values().collect(), total, silence, overlap)
+}
+
+/// 统计各说话人的发言情况
+///
+/// 在本地计算，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+/// `details` / `details_len`，时间单位为秒，顺序不限。
+/// 未识别的说话人（编号为负）不出现在 `speakers` 中，但其发言时段不计入静默。
+///
+/// # 参数
+/// - `utterances`: utterance 数组指针
+/// - `utterances_len`: utterance 数量
+/// - `out_stats`: 输出统计结构体指针，需调用 `transcribe_ffi_free_speaker_stats` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_speaker_stats(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    out_stats: *mut FfiSpeakerStats,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_stats.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+        let utterances = utterances_from_raw(utterances, utterances_len)?;
+
+        let (speakers, total_time, silence_time, overlap_time) = compute(utterances);
+        let speakers_len = speakers.len();
+        let speakers = if speakers_len > 0 {
+            Box::into_raw(speakers.into_boxed_slice()) as *mut FfiSpeakerStat
+        } else {
+            std::ptr::null_mut()
+        };
+        unsafe {
+            *out_stats = FfiSpeakerStats {
+                speakers,
+                speakers_len,
+                total_time,
+                silence_time,
+                overlap_time,
+            };
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_speaker_stats(s: *mut FfiSpeakerStats) {
+    if s.is_null() {
+        return;
+    }
+    unsafe {
+        let s = &mut *s;
+        if !s.speakers.is_null() && s.speakers_len > 0 {
+            let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                s.speakers,
+                s.speakers_len,
+            ));
+        }
+        s.speakers = std::ptr::null_mut();
+        s.speakers_len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(start: f64, end: f64, speaker: i32, text: &str) -> Utterance {
+        Utterance {
+            start_time: start,
+            end_time: end,
+            speaker,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn counts_words() {
+        assert_eq!(count_words("Hello, world! 你好 it's well-known 42"), 7);
+        assert_eq!(count_words("- ' --"), 0);
+        assert_eq!(count_words(""), 0);
+    }
+
+    #[test]
+    fn computes_turns_interruptions_silence_and_overlap() {
+        let utterances = vec![
+            utterance(6.0, 8.0, 0, "it's well-known"),
+            utterance(0.0, 4.0, 0, "hello world"),
+            utterance(3.0, 5.0, 1, "你好"),
+            utterance(8.0, 9.0, 0, "again"),
+        ];
+        let (stats, total, silence, overlap) = compute(utterances);
+        assert_eq!((total, silence, overlap), (9.0, 1.0, 1.0));
+
+        let [a, b] = &stats[..] else {
+            panic!("expected two speakers");
+        };
+        assert_eq!((a.speaker, a.turns, a.interruptions, a.words), (0, 2, 0, 5));
+        assert_eq!((b.speaker, b.turns, b.interruptions, b.words), (1, 1, 1, 2));
+        assert_eq!((a.talk_time, b.talk_time), (7.0, 2.0));
+        assert_eq!(a.longest_monologue, 4.0);
+        assert_eq!(a.talk_ratio, 7.0 / 9.0);
+        assert_eq!(b.words_per_minute, 60.0);
+    }
+
+    #[test]
+    fn overlap_counts_distinct_speakers() {
+        // 同一说话人的 utterance 相互重叠不算重叠，未识别的说话人不参与重叠
+        let utterances = vec![
+            utterance(0.0, 4.0, 0, "a"),
+            utterance(1.0, 3.0, 0, "b"),
+            utterance(2.0, 5.0, -1, "c"),
+            utterance(6.0, 8.0, 1, "d"),
+            utterance(7.0, 9.0, 2, "e"),
+        ];
+        let (stats, total, silence, overlap) = compute(utterances);
+        assert_eq!((total, silence, overlap), (9.0, 1.0, 1.0));
+        assert_eq!(
+            stats.iter().map(|s| s.speaker).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert_eq!(stats[0].interruptions, 0);
+        assert_eq!(stats[2].interruptions, 1);
+    }
+
+    #[test]
+    fn handles_empty_input() {
+        let (stats, total, silence, overlap) = compute(Vec::new());
+        assert!(stats.is_empty());
+        assert_eq!((total, silence, overlap), (0.0, 0.0, 0.0));
+    }
+}
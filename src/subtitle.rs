@@ -0,0 +1,557 @@
+use std::ffi::*;
+use std::fmt::Write;
+
+use common::Error;
+use transcribe::types::Utterance;
+
+use crate::{error::FfiError, utils::*, FfiUtterance};
+
+/// 折行时优先在其后断开的标点
+const BREAK_AFTER: &[char] = &[
+    '，', '。', '！', '？', '、', '；', '：', ',', '.', '!', '?', ';', ':',
+];
+
+/// 字幕格式
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+/// 解析字幕格式（"srt"、"vtt" / "webvtt"，不区分大小写）
+pub(crate) fn subtitle_format_from_str(s: &str) -> Result<SubtitleFormat, Error> {
+    match s.to_ascii_lowercase().as_str() {
+        "srt" => Ok(SubtitleFormat::Srt),
+        "vtt" | "webvtt" => Ok(SubtitleFormat::WebVtt),
+        _ => Err(Error::InvalidInput("Invalid subtitle format".to_string())),
+    }
+}
+
+/// 字幕渲染选项，所有字段为 0 / null 时与默认选项相同
+///
+/// 调用方须先用 `transcribe_ffi_subtitle_options_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiSubtitleOptions)` 并将其余字段清零）。新字段只会追加在末尾，未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiSubtitleOptions {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 字幕格式（C 字符串，"srt" 或 "vtt"，为 null 时使用 "srt"）
+    pub format: *const c_char,
+    /// 说话人标签（C 字符串，可为 null），其中的 "{}" 替换为说话人编号，例如 "Speaker {}"。
+    /// SRT 中作为 "标签: " 前缀，WebVTT 中作为 `<v 标签>` 声音标记；为 null 时不标注说话人
+    pub speaker_label: *const c_char,
+    /// 每行最大字符数，超出时折行（优先在空格和标点处），0 表示不折行
+    pub max_line_length: usize,
+    /// 每条字幕的最大行数，超出时按字数比例拆分为多条字幕，0 表示不限制
+    pub max_lines: usize,
+    /// 每条字幕的最短显示时间（毫秒），不足时延长结束时间（不与下一条重叠）
+    pub min_duration_ms: u64,
+    /// 所有时间的偏移（毫秒），可为负，偏移后结束时间不大于 0 的字幕会被丢弃
+    pub offset_ms: i64,
+}
+
+/// 解析后的字幕渲染选项
+pub(crate) struct SubtitleOptions {
+    pub format: SubtitleFormat,
+    pub speaker_label: Option<String>,
+    pub max_line_length: usize,
+    pub max_lines: usize,
+    /// 最短显示时间（秒）
+    pub min_duration: f64,
+    /// 时间偏移（秒）
+    pub offset: f64,
+    /// 双语字幕中译文是否在原文之前
+    pub translation_first: bool,
+}
+
+impl Default for SubtitleOptions {
+    fn default() -> Self {
+        Self {
+            format: SubtitleFormat::Srt,
+            speaker_label: None,
+            max_line_length: 0,
+            max_lines: 0,
+            min_duration: 0.0,
+            offset: 0.0,
+            translation_first: false,
+        }
+    }
+}
+
+impl TryFrom<&FfiSubtitleOptions> for SubtitleOptions {
+    type Error = Error;
+
+    fn try_from(o: &FfiSubtitleOptions) -> Result<Self, Self::Error> {
+        let speaker_label = if o.speaker_label.is_null() {
+            None
+        } else {
+            Some(parse_c_str(o.speaker_label, |s| Ok(s.to_string()))?).filter(|s| !s.is_empty())
+        };
+
+        let format = if o.format.is_null() {
+            SubtitleFormat::Srt
+        } else {
+            parse_c_str(o.format, subtitle_format_from_str)?
+        };
+
+        Ok(Self {
+            format,
+            speaker_label,
+            max_line_length: o.max_line_length,
+            max_lines: o.max_lines,
+            min_duration: o.min_duration_ms as f64 / 1000.0,
+            offset: o.offset_ms as f64 / 1000.0,
+            translation_first: false,
+        })
+    }
+}
+
+impl SubtitleOptions {
+    /// 从 C 指针读取选项，为 null 时使用默认选项
+    pub fn from_ptr(options: *const FfiSubtitleOptions) -> Result<Self, Error> {
+        match unsafe { read_sized_struct(options, 0)? } {
+            Some(options) => SubtitleOptions::try_from(&options),
+            None => Ok(SubtitleOptions::default()),
+        }
+    }
+
+    fn label(&self, speaker: i32) -> Option<String> {
+        speaker_label(self.speaker_label.as_deref(), speaker)
+    }
+}
+
+/// 替换标签中的说话人编号，说话人编号为负（未知）时为 None
+pub(crate) fn speaker_label(label: Option<&str>, speaker: i32) -> Option<String> {
+    let label = label?;
+    (speaker >= 0).then(|| label.replace("{}", &speaker.to_string()))
+}
+
+/// 一条待渲染的 utterance 及其译文
+pub(crate) struct SubtitleLine {
+    pub utterance: Utterance,
+    pub translation: Option<String>,
+}
+
+/// 一条字幕
+struct Cue {
+    start: f64,
+    end: f64,
+    label: Option<String>,
+    lines: Vec<String>,
+}
+
+/// 按最大字符数折行，`max` 为 0 时只合并空白
+fn wrap(text: &str, max: usize) -> Vec<String> {
+    wrap_with_prefix("", text, max)
+}
+
+/// 按最大字符数折行，并在首行前加上 `prefix`
+///
+/// 前缀计入首行的长度，但不会单独成行
+fn wrap_with_prefix(prefix: &str, text: &str, max: usize) -> Vec<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if max == 0 {
+        return vec![format!("{}{}", prefix, text)];
+    }
+
+    // 前缀过长时首行至少保留一个字符
+    let first_max = max.saturating_sub(prefix.chars().count()).max(1);
+    let mut lines: Vec<String> = Vec::new();
+    let mut rest: Vec<char> = text.chars().collect();
+    loop {
+        let max = if lines.is_empty() { first_max } else { max };
+        if rest.len() <= max {
+            break;
+        }
+        // 在空格处断开时丢弃空格，在标点处断开时标点留在行尾
+        let (end, next) = (1..=max)
+            .rev()
+            .find_map(|i| {
+                if rest[i] == ' ' {
+                    Some((i, i + 1))
+                } else if BREAK_AFTER.contains(&rest[i - 1]) {
+                    Some((i, i))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or((max, max));
+        lines.push(
+            rest[..end]
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string(),
+        );
+        rest.drain(..next);
+        while rest.first() == Some(&' ') {
+            rest.remove(0);
+        }
+    }
+    if !rest.is_empty() {
+        lines.push(rest.into_iter().collect());
+    }
+    if let Some(first) = lines.first_mut() {
+        first.insert_str(0, prefix);
+    }
+    lines
+}
+
+/// 将 utterance 转换为字幕，按开始时间排序
+fn build_cues(items: &[SubtitleLine], options: &SubtitleOptions) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    for item in items {
+        let u = &item.utterance;
+        let translation = item.translation.as_deref().filter(|t| !t.trim().is_empty());
+        let end = u.end_time + options.offset;
+        // 结束时间为 NaN 时同样丢弃
+        if (u.text.trim().is_empty() && translation.is_none()) || end.is_nan() || end <= 0.0 {
+            continue;
+        }
+        let start = (u.start_time + options.offset).max(0.0).min(end);
+
+        let (first, second) = match translation {
+            Some(t) if options.translation_first => (t, Some(u.text.as_str())),
+            Some(t) => (u.text.as_str(), Some(t)),
+            None => (u.text.as_str(), None),
+        };
+
+        // SRT 的说话人前缀计入首行行长，WebVTT 的声音标记不显示为文本
+        let label = options.label(u.speaker);
+        let prefix = match (&label, options.format) {
+            (Some(label), SubtitleFormat::Srt) => format!("{}: ", label),
+            _ => String::new(),
+        };
+        let mut lines = wrap_with_prefix(&prefix, first, options.max_line_length);
+        if let Some(second) = second {
+            lines.extend(wrap(second, options.max_line_length));
+        }
+        lines.retain(|l| !l.is_empty());
+
+        // 双语字幕不拆分，以免原文与译文出现在不同的字幕中
+        if second.is_some() || options.max_lines == 0 || lines.len() <= options.max_lines {
+            cues.push(Cue {
+                start,
+                end,
+                label,
+                lines,
+            });
+            continue;
+        }
+
+        // 行数超出时拆分为多条，时间按字数比例分配
+        let chunks: Vec<&[String]> = lines.chunks(options.max_lines).collect();
+        let total: usize = lines.iter().map(|l| l.chars().count()).sum();
+        let mut chunk_start = start;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let chars: usize = chunk.iter().map(|l| l.chars().count()).sum();
+            let chunk_end = if i + 1 == chunks.len() {
+                end
+            } else {
+                chunk_start + (end - start) * chars as f64 / total.max(1) as f64
+            };
+            cues.push(Cue {
+                start: chunk_start,
+                end: chunk_end,
+                label: label.clone(),
+                lines: chunk.to_vec(),
+            });
+            chunk_start = chunk_end;
+        }
+    }
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    // 延长过短的字幕，但不覆盖下一条
+    if options.min_duration > 0.0 {
+        for i in 0..cues.len() {
+            let min_end = cues[i].start + options.min_duration;
+            if cues[i].end >= min_end {
+                continue;
+            }
+            let limit = cues
+                .get(i + 1)
+                .map(|next| next.start)
+                .unwrap_or(f64::INFINITY);
+            cues[i].end = min_end.min(limit).max(cues[i].end);
+        }
+    }
+    cues
+}
+
+/// 格式化时间戳，`separator` 为秒与毫秒之间的分隔符
+fn timestamp(seconds: f64, separator: char) -> String {
+    let ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// 转义 WebVTT 字幕文本中的特殊字符
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 将 utterance（及译文）渲染为 SRT 或 WebVTT 字幕
+pub(crate) fn render(items: &[SubtitleLine], options: &SubtitleOptions) -> String {
+    let cues = build_cues(items, options);
+    let mut out = String::new();
+    match options.format {
+        SubtitleFormat::Srt => {
+            for (i, cue) in cues.iter().enumerate() {
+                let _ = writeln!(out, "{}", i + 1);
+                let _ = writeln!(
+                    out,
+                    "{} --> {}",
+                    timestamp(cue.start, ','),
+                    timestamp(cue.end, ',')
+                );
+                for line in &cue.lines {
+                    let _ = writeln!(out, "{}", line);
+                }
+                out.push('\n');
+            }
+        }
+        SubtitleFormat::WebVtt => {
+            out.push_str("WEBVTT\n\n");
+            for cue in &cues {
+                let _ = writeln!(
+                    out,
+                    "{} --> {}",
+                    timestamp(cue.start, '.'),
+                    timestamp(cue.end, '.')
+                );
+                for (i, line) in cue.lines.iter().enumerate() {
+                    match &cue.label {
+                        Some(label) if i == 0 => {
+                            let _ = writeln!(out, "<v {}>{}", escape_vtt(label), escape_vtt(line));
+                        }
+                        _ => {
+                            let _ = writeln!(out, "{}", escape_vtt(line));
+                        }
+                    }
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// 从 C 数组读取 utterance，文本为 null 或时间不是有限数时返回错误
+pub(crate) fn utterances_from_raw(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+) -> Result<Vec<Utterance>, Error> {
+    if utterances_len == 0 {
+        return Ok(Vec::new());
+    }
+    if utterances.is_null() {
+        return Err(Error::InvalidInput("Invalid utterances".to_string()));
+    }
+    let utterances = unsafe { std::slice::from_raw_parts(utterances, utterances_len) };
+    utterances.iter().try_for_each(check_utterance)?;
+    Ok(utterances.iter().map(Utterance::from).collect())
+}
+
+/// 检查 utterance 的文本不为 null 且时间为有限数
+pub(crate) fn check_utterance(u: &FfiUtterance) -> Result<(), Error> {
+    if u.text.is_null() {
+        return Err(Error::InvalidInput("Utterance text is null".to_string()));
+    }
+    if !u.start_time.is_finite() || !u.end_time.is_finite() {
+        return Err(Error::InvalidInput(
+            "Utterance time is not a finite number".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 将 utterance 数组渲染为 SRT 或 WebVTT 字幕
+///
+/// 在本地渲染，不请求服务端。`utterances` 可直接使用 `FfiTranscribeStatus` 的
+/// `details` / `details_len`，时间单位为秒。
+///
+/// # 参数
+/// - `utterances`: utterance 数组指针
+/// - `utterances_len`: utterance 数量
+/// - `options`: 渲染选项，为 null 时使用默认选项（SRT，不标注说话人，不折行）
+/// - `out_text`: 输出字幕文本指针，需调用 `transcribe_ffi_free_string` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_render_subtitles(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    options: *const FfiSubtitleOptions,
+    out_text: *mut *mut c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_text.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+        }
+        let utterances = utterances_from_raw(utterances, utterances_len)?;
+        let options = SubtitleOptions::from_ptr(options)?;
+
+        let items: Vec<SubtitleLine> = utterances
+            .into_iter()
+            .map(|utterance| SubtitleLine {
+                utterance,
+                translation: None,
+            })
+            .collect();
+        let text = render(&items, &options);
+        let text = CString::new(text).map_err(|e| Error::OtherError(e.to_string()))?;
+        unsafe {
+            *out_text = text.into_raw();
+        }
+
+        Ok(())
+    })
+}
+
+/// 初始化字幕渲染选项
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `options`: 字幕渲染选项结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_subtitle_options_init(options: *mut FfiSubtitleOptions) {
+    if options.is_null() {
+        return;
+    }
+
+    unsafe {
+        *options = FfiSubtitleOptions {
+            struct_size: std::mem::size_of::<FfiSubtitleOptions>(),
+            format: std::ptr::null(),
+            speaker_label: std::ptr::null(),
+            max_line_length: 0,
+            max_lines: 0,
+            min_duration_ms: 0,
+            offset_ms: 0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(start: f64, end: f64, speaker: i32, text: &str) -> SubtitleLine {
+        SubtitleLine {
+            utterance: Utterance {
+                start_time: start,
+                end_time: end,
+                speaker,
+                text: text.to_string(),
+            },
+            translation: None,
+        }
+    }
+
+    #[test]
+    fn wrap_prefers_spaces_and_punctuation() {
+        assert_eq!(wrap("  hello   world ", 0), ["hello world"]);
+        assert_eq!(wrap("hello world again", 11), ["hello world", "again"]);
+        assert_eq!(wrap("你好，世界你好", 4), ["你好，", "世界你好"]);
+        assert_eq!(wrap("abcdefgh", 3), ["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn wrap_never_leaves_prefix_alone() {
+        assert_eq!(
+            wrap_with_prefix("S1: ", "hello world", 9),
+            ["S1: hello", "world"]
+        );
+        assert_eq!(wrap_with_prefix("S1: ", "hello", 4), ["S1: h", "ello"]);
+        assert_eq!(wrap_with_prefix("S1: ", " hi ", 0), ["S1: hi"]);
+    }
+
+    #[test]
+    fn build_cues_sorts_offsets_and_drops() {
+        let options = SubtitleOptions {
+            offset: -1.0,
+            ..SubtitleOptions::default()
+        };
+        let items = [
+            line(5.0, 6.0, 0, "second"),
+            line(0.5, 2.0, 0, "first"),
+            line(0.0, 0.8, 0, "gone"),
+            line(3.0, 4.0, 0, "  "),
+        ];
+        let cues = build_cues(&items, &options);
+        let starts: Vec<_> = cues.iter().map(|c| (c.start, c.end)).collect();
+        assert_eq!(starts, [(0.0, 1.0), (4.0, 5.0)]);
+        assert_eq!(cues[0].lines, ["first"]);
+    }
+
+    #[test]
+    fn build_cues_splits_lines_and_extends_short_cues() {
+        let options = SubtitleOptions {
+            max_line_length: 8,
+            max_lines: 1,
+            min_duration: 2.0,
+            speaker_label: Some("S{}".to_string()),
+            ..SubtitleOptions::default()
+        };
+        let items = [line(0.0, 2.0, 1, "aaaa bbbb"), line(2.5, 2.6, -1, "cc")];
+        let cues = build_cues(&items, &options);
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[0].lines, ["S1: aaaa"]);
+        assert_eq!(cues[0].label.as_deref(), Some("S1"));
+        assert_eq!(cues[1].lines, ["bbbb"]);
+        assert_eq!(cues[1].end, 2.5);
+        assert_eq!(cues[2].label, None);
+        assert_eq!(cues[2].end, 4.5);
+    }
+
+    #[test]
+    fn build_cues_tolerates_nan_times() {
+        let items = [
+            line(0.0, f64::NAN, 0, "nan end"),
+            line(f64::NAN, 1.0, 0, "nan start"),
+        ];
+        let cues = build_cues(&items, &SubtitleOptions::default());
+        assert_eq!(cues.len(), 1);
+        assert_eq!((cues[0].start, cues[0].end), (0.0, 1.0));
+    }
+
+    #[test]
+    fn rejects_non_finite_times() {
+        let text = CString::new("x").unwrap();
+        let utterance = FfiUtterance {
+            start_time: 0.0,
+            end_time: f64::NAN,
+            speaker: 0,
+            text: text.as_ptr() as *mut c_char,
+        };
+        assert!(matches!(
+            utterances_from_raw(&utterance, 1),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn renders_vtt_with_voice_tags() {
+        let options = SubtitleOptions {
+            format: SubtitleFormat::WebVtt,
+            speaker_label: Some("Speaker {}".to_string()),
+            ..SubtitleOptions::default()
+        };
+        let text = render(&[line(1.0, 2.5, 0, "a < b")], &options);
+        assert_eq!(
+            text,
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n<v Speaker 0>a &lt; b\n\n"
+        );
+    }
+}
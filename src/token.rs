@@ -0,0 +1,162 @@
+use std::ffi::*;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use common::Error;
+use tokio::sync::Notify;
+
+use crate::{error::FfiError, operation::UserData, utils::ffi_execute};
+
+/// token 提供回调
+///
+/// 参数为失效的 token 和 user_data，返回新的 token（C 字符串，本库会立即复制，
+/// 所有权仍归宿主），无法提供时返回 null。回调可能在任意线程（包括 runtime 工作线程）上调用，
+/// 同一时刻最多只有一个调用。
+pub type FfiTokenProvider =
+    Option<extern "C" fn(expired_token: *const c_char, user_data: *mut c_void) -> *const c_char>;
+
+/// 宿主回调、最近一次刷新（失效 token, 新 token）以及正在进行的刷新（失效 token, 完成通知）
+struct TokenState {
+    provider: FfiTokenProvider,
+    user_data: UserData,
+    last_refresh: Option<(String, String)>,
+    refreshing: Option<(String, Arc<Notify>)>,
+}
+
+static TOKEN_STATE: Mutex<TokenState> = Mutex::new(TokenState {
+    provider: None,
+    user_data: UserData::new(std::ptr::null_mut()),
+    last_refresh: None,
+    refreshing: None,
+});
+
+fn token_state() -> std::sync::MutexGuard<'static, TokenState> {
+    TOKEN_STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 获取 token 的最新值
+///
+/// 调用方持有的 token 已被刷新时返回刷新后的 token，长期持有 token 的流和轮询无需感知轮换
+pub(crate) fn current(token: &str) -> String {
+    let state = token_state();
+    match &state.last_refresh {
+        Some((expired, fresh)) if expired == token => fresh.clone(),
+        _ => token.to_string(),
+    }
+}
+
+/// 调用宿主回调获取新 token
+fn call_provider(provider: FfiTokenProvider, user_data: UserData, expired: &str) -> Option<String> {
+    let provider = provider?;
+    let expired_c = CString::new(expired).ok()?;
+    let fresh = provider(expired_c.as_ptr(), user_data.get());
+    if fresh.is_null() {
+        log::warn!("Token provider returned no token");
+        return None;
+    }
+
+    let fresh = unsafe { CStr::from_ptr(fresh) }.to_str().ok()?.to_string();
+    if fresh.is_empty() || fresh == expired {
+        log::warn!("Token provider returned the expired token");
+        return None;
+    }
+    log::info!("Token refreshed by provider");
+    Some(fresh)
+}
+
+/// 通过宿主回调刷新失效的 token
+///
+/// 调用回调期间不持有锁。已有刷新在进行时等待其完成：刷新的是同一个 token 时直接使用其结果
+/// （包括失败），因此并发请求同时失效时只调用一次回调
+async fn refresh(expired: &str) -> Option<String> {
+    loop {
+        let (pending, same_token) = {
+            let mut state = token_state();
+            if let Some((last_expired, fresh)) = &state.last_refresh {
+                if last_expired == expired {
+                    return Some(fresh.clone());
+                }
+            }
+
+            match &state.refreshing {
+                // 在释放锁之前注册等待，避免错过完成通知
+                Some((refreshing, notify)) => {
+                    (notify.clone().notified_owned(), refreshing == expired)
+                }
+                None => {
+                    let (provider, user_data) = (state.provider, state.user_data);
+                    provider?;
+                    let notify = Arc::new(Notify::new());
+                    state.refreshing = Some((expired.to_string(), notify.clone()));
+                    drop(state);
+
+                    let fresh = call_provider(provider, user_data, expired);
+
+                    let mut state = token_state();
+                    if let Some(fresh) = &fresh {
+                        state.last_refresh = Some((expired.to_string(), fresh.clone()));
+                    }
+                    state.refreshing = None;
+                    notify.notify_waiters();
+                    return fresh;
+                }
+            }
+        };
+
+        pending.await;
+        if same_token {
+            return match &token_state().last_refresh {
+                Some((last_expired, fresh)) if last_expired == expired => Some(fresh.clone()),
+                _ => None,
+            };
+        }
+    }
+}
+
+/// 执行请求，token 失效（`InvalidToken` / `InvalidApiKey`）时向宿主获取新 token 并重试一次
+///
+/// `f` 以 token 为参数构造请求 future
+pub(crate) async fn with_token_refresh<T, F, Fut>(token: &str, f: F) -> Result<T, Error>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let token = current(token);
+    match f(token.clone()).await {
+        Err(e @ (Error::InvalidToken(_) | Error::InvalidApiKey(_))) => {
+            match refresh(&token).await {
+                Some(fresh) => f(fresh).await,
+                None => Err(e),
+            }
+        }
+        result => result,
+    }
+}
+
+/// 设置 token 提供回调
+///
+/// 请求因 token 失效（`InvalidToken` / `InvalidApiKey`）失败时，本库调用回调获取新 token
+/// 并透明地重试一次。之后使用旧 token 的调用（包括客户端句柄、实时转写会话的关闭等）
+/// 会自动改用新 token。
+///
+/// # 参数
+/// - `provider`: token 提供回调，传 null 取消
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_set_token_provider(
+    provider: FfiTokenProvider,
+    user_data: *mut c_void,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let mut state = token_state();
+        state.provider = provider;
+        state.user_data = UserData::new(user_data);
+        state.last_refresh = None;
+
+        Ok::<(), Error>(())
+    })
+}
@@ -1,4 +1,7 @@
 use std::ffi::*;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use transcribe::{
     transcribe::{
         callback, create_summary, export, get_share_link, status, upload, CallbackRequest,
@@ -8,14 +11,61 @@ use transcribe::{
 };
 
 use crate::{
-    error::FfiError, runtime::get_runtime, utils::*, FfiCallbackRequest, FfiCallbackResponse,
-    FfiShareLink, FfiSummaryCreator, FfiTextTranslator, FfiTranscribeStatus,
-    FfiTranscribeTranslator, FfiUploadResponse, FfiUtterance, FfiUtteranceTranslator,
+    client::DianyaClient,
+    error::FfiError,
+    metrics::{track, track_export, track_upload, Operation},
+    retry::block_on_idempotent,
+    runtime::block_on,
+    token::with_token_refresh,
+    utils::*,
+    FfiBytes, FfiCallbackRequest, FfiCallbackResponse, FfiShareLink, FfiSummaryCreator,
+    FfiTextTranslator, FfiTranscribeStatus, FfiTranscribeTranslator, FfiUploadResponse,
+    FfiUtterance, FfiUtteranceTranslator,
 };
 use common::Error;
 
+/// 校验后的导出参数
+///
+/// SDK 的 `ExportType` / `ExportFormat` 无法 Clone，这里保存校验过的字符串，每次请求时再转换
+pub(crate) struct ExportArgs {
+    task_id: String,
+    export_type: String,
+    export_format: String,
+}
+
+impl ExportArgs {
+    /// 发起一次导出请求
+    pub(crate) async fn export(&self, token: &str) -> Result<Vec<u8>, Error> {
+        let export_type = transcribe_export_type_from_str(&self.export_type)?;
+        let export_format = format_type_from_str(&self.export_format)?;
+        track_export(export(&self.task_id, export_type, export_format, token)).await
+    }
+}
+
+/// 读取并校验导出参数
+pub(crate) fn parse_export_args(
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+) -> Result<ExportArgs, Error> {
+    let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+    let export_type = parse_c_str(export_type, |s| Ok(s.to_string()))?;
+    let export_format = parse_c_str(export_format, |s| Ok(s.to_string()))?;
+    transcribe_export_type_from_str(&export_type)?;
+    format_type_from_str(&export_format)?;
+
+    Ok(ExportArgs {
+        task_id,
+        export_type,
+        export_format,
+    })
+}
+
 /// 导出转写内容或总结内容
 ///
+/// 缓冲区不足时会返回错误并写入所需大小；如不想预先分配缓冲区，
+/// 请使用 `transcribe_ffi_export_bytes`。
+///
 /// # 参数
 /// - `task_id`: 任务ID（C 字符串）
 /// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
@@ -38,24 +88,20 @@ pub extern "C" fn transcribe_ffi_export(
 ) -> c_int {
     ffi_execute(out_error, || {
         if result_data.is_null() || result_len.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
-        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
-        let export_type = parse_transcribe_export_type(export_type)?;
-        let export_format = parse_format_type(export_format)?;
+        let args = parse_export_args(task_id, export_type, export_format)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
 
-        let data = get_runtime().block_on(export(&task_id, export_type, export_format, &token))?;
+        let args = &args;
+        let data = block_on_idempotent(&token, |token| async move { args.export(&token).await })?;
         let len = data.len();
         let buffer_size = unsafe { *result_len };
 
         if len > buffer_size {
             unsafe { *result_len = len };
-            return Err(Error::InvalidInput(format!(
-                "Buffer too small, need {} bytes",
-                len
-            )));
+            return Err(FfiError::buffer_too_small(len));
         }
 
         unsafe {
@@ -63,7 +109,118 @@ pub extern "C" fn transcribe_ffi_export(
             *result_len = len;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 导出转写内容或总结内容，数据由本库分配
+///
+/// 与 `transcribe_ffi_export` 不同，无需预先知道数据大小，每次导出只请求一次服务器。
+/// 成功后需调用 `transcribe_ffi_free_bytes` 释放 `out_bytes`。
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `token`: Bearer token（C 字符串）
+/// - `out_bytes`: 输出二进制数据结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_bytes(
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    token: *const c_char,
+    out_bytes: *mut FfiBytes,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_bytes.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let args = parse_export_args(task_id, export_type, export_format)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let args = &args;
+        let data = block_on_idempotent(&token, |token| async move { args.export(&token).await })?;
+        unsafe {
+            *out_bytes = FfiBytes::from(data);
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 先写入同目录下的临时文件，再重命名为目标文件
+///
+/// 目标文件要么保持原样，要么被完整替换；失败时删除临时文件
+fn write_file_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Not a file path"))?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = std::fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// 导出转写内容或总结内容并写入文件
+///
+/// 数据先写入同目录下的临时文件，完成后再替换目标文件，
+/// 失败时目标文件保持不变，不会留下不完整的文件。
+///
+/// 注意：SDK 的导出接口一次性返回完整内容，不支持分块读取，因此整个文档仍会先缓存在
+/// 内存中再写入文件，内存占用与文档大小相同。本函数只省去了调用方分配缓冲区和写文件的步骤。
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `token`: Bearer token（C 字符串）
+/// - `path`: 目标文件路径（C 字符串）
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_to_file(
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    token: *const c_char,
+    path: *const c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let args = parse_export_args(task_id, export_type, export_format)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let path = parse_c_str(path, |s| Ok(s.to_string()))?;
+
+        let args = &args;
+        let data = block_on_idempotent(&token, |token| async move { args.export(&token).await })?;
+        write_file_atomic(Path::new(&path), &data)
+            .map_err(|e| Error::OtherError(format!("Failed to write file {}: {}", path, e)))?;
+
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -87,7 +244,7 @@ pub extern "C" fn transcribe_ffi_get_share_link(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_link.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
@@ -98,7 +255,10 @@ pub extern "C" fn transcribe_ffi_get_share_link(
             Some(expiration_day)
         };
 
-        let link = get_runtime().block_on(get_share_link(&task_id, expiration_opt, &token))?;
+        let task_id = task_id.as_str();
+        let link = block_on_idempotent(&token, |token| async move {
+            get_share_link(task_id, expiration_opt, &token).await
+        })?;
         let ffi_link = FfiShareLink::try_from(link)?;
 
         unsafe {
@@ -107,7 +267,7 @@ pub extern "C" fn transcribe_ffi_get_share_link(
             (*out_link).expired_at = ffi_link.expired_at;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -131,7 +291,7 @@ pub extern "C" fn transcribe_ffi_get_status(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_status.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let task_id_opt = if task_id.is_null() {
@@ -146,11 +306,10 @@ pub extern "C" fn transcribe_ffi_get_status(
         };
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
 
-        let status_response = get_runtime().block_on(status(
-            task_id_opt.as_deref(),
-            share_id_opt.as_deref(),
-            &token,
-        ))?;
+        let (task_id, share_id) = (task_id_opt.as_deref(), share_id_opt.as_deref());
+        let status_response = block_on_idempotent(&token, |token| async move {
+            track(Operation::GetStatus, status(task_id, share_id, &token)).await
+        })?;
 
         let ffi_status = FfiTranscribeStatus::try_from(status_response)?;
 
@@ -171,7 +330,7 @@ pub extern "C" fn transcribe_ffi_get_status(
             (*out_status).has_task_type = ffi_status.has_task_type;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -195,31 +354,23 @@ pub extern "C" fn transcribe_ffi_create_summary(
 ) -> c_int {
     ffi_execute(out_error, || {
         if utterances.is_null() || utterances_len == 0 || out_summary.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
 
-        let utterances: Vec<Utterance> = unsafe {
-            std::slice::from_raw_parts(utterances, utterances_len)
-                .into_iter()
-                .map(|u| Utterance {
-                    start_time: u.start_time,
-                    end_time: u.end_time,
-                    speaker: u.speaker,
-                    text: CStr::from_ptr(u.text).to_string_lossy().to_string(),
-                })
-                .collect()
-        };
-
-        let response = get_runtime().block_on(create_summary(utterances, &token))?;
+        let utterances = unsafe { std::slice::from_raw_parts(utterances, utterances_len) };
+        let response = block_on(with_token_refresh(&token, |token| {
+            let utterances: Vec<Utterance> = utterances.iter().map(Utterance::from).collect();
+            async move { create_summary(utterances, &token).await }
+        }))?;
         let ffi_summary = FfiSummaryCreator::try_from(response)?;
 
         unsafe {
             (*out_summary).task_id = ffi_summary.task_id;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -247,14 +398,22 @@ pub extern "C" fn transcribe_ffi_upload(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_result.is_null() {
-            return Err(Error::InvalidInput("Invalid output parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
         }
         let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
-        let model = parse_model_type(model)?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
 
-        let result =
-            get_runtime().block_on(upload(&filepath, transcribe_only, short_asr, model, &token))?;
+        let (filepath, model) = (filepath.as_str(), model.as_str());
+        let result = block_on(with_token_refresh(&token, |token| async move {
+            let model = model_type_from_str(model)?;
+            track_upload(
+                filepath,
+                upload(filepath, transcribe_only, short_asr, model, &token),
+            )
+            .await
+        }))?;
 
         let ffi_result = FfiUploadResponse::try_from(result)?;
         unsafe {
@@ -263,7 +422,7 @@ pub extern "C" fn transcribe_ffi_upload(
             (*out_result).one_sentence = ffi_result.one_sentence;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -287,20 +446,23 @@ pub extern "C" fn transcribe_ffi_translate_text(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_result.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let text = parse_c_str(text, |s| Ok(s.to_string()))?;
         let lang = parse_language(target_lang)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
-        let result = get_runtime().block_on(translate_text(&text, lang, &token))?;
+        let text = text.as_str();
+        let result = block_on(with_token_refresh(&token, |token| async move {
+            track(Operation::TranslateText, translate_text(text, lang, &token)).await
+        }))?;
         let result = FfiTextTranslator::try_from(result)?;
         unsafe {
             (*out_result).status = result.status;
             (*out_result).data = result.data;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -326,24 +488,22 @@ pub extern "C" fn transcribe_ffi_translate_utterance(
 ) -> c_int {
     ffi_execute(out_error, || {
         if utterances.is_null() || utterances_len == 0 || out_result.is_null() {
-            return Err(Error::InvalidInput("Invalid input".to_string()));
-        }
-
-        let utterances: Vec<Utterance> = unsafe {
-            std::slice::from_raw_parts(utterances, utterances_len)
-                .into_iter()
-                .map(|u| Utterance {
-                    start_time: u.start_time,
-                    end_time: u.end_time,
-                    speaker: u.speaker,
-                    text: CStr::from_ptr(u.text).to_string_lossy().to_string(),
-                })
-                .collect()
-        };
+            return Err(Error::InvalidInput("Invalid input".to_string()).into());
+        }
 
+        let utterances = unsafe { std::slice::from_raw_parts(utterances, utterances_len) };
         let lang = parse_language(target_lang)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
-        let result = get_runtime().block_on(translate_utterance(utterances, lang, &token))?;
+        let result = block_on(with_token_refresh(&token, |token| {
+            let utterances: Vec<Utterance> = utterances.iter().map(Utterance::from).collect();
+            async move {
+                track(
+                    Operation::TranslateUtterance,
+                    translate_utterance(utterances, lang, &token),
+                )
+                .await
+            }
+        }))?;
         let result = FfiUtteranceTranslator::try_from(result)?;
         unsafe {
             (*out_result).status = result.status;
@@ -352,7 +512,7 @@ pub extern "C" fn transcribe_ffi_translate_utterance(
             (*out_result).details_len = result.details_len;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -376,13 +536,20 @@ pub extern "C" fn transcribe_ffi_translate_transcribe(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_result.is_null() {
-            return Err(Error::InvalidInput("out_result is null".to_string()));
+            return Err(Error::InvalidInput("out_result is null".to_string()).into());
         }
 
         let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
         let lang = parse_language(target_lang)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
-        let result = get_runtime().block_on(translate_transcribe(&task_id, lang, &token))?;
+        let task_id = task_id.as_str();
+        let result = block_on_idempotent(&token, |token| async move {
+            track(
+                Operation::TranslateTranscribe,
+                translate_transcribe(task_id, lang, &token),
+            )
+            .await
+        })?;
 
         let result = FfiTranscribeTranslator::try_from(result)?;
         unsafe {
@@ -399,7 +566,7 @@ pub extern "C" fn transcribe_ffi_translate_transcribe(
             (*out_result).keywords_len = result.keywords_len;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -421,17 +588,457 @@ pub extern "C" fn transcribe_ffi_callback(
 ) -> c_int {
     ffi_execute(out_error, || {
         if request.is_null() || out_response.is_null() {
-            return Err(Error::InvalidInput("Invalid input parameters".to_string()));
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
-        let request = CallbackRequest::from(unsafe { &*request });
-        let result = get_runtime().block_on(callback(&request, &token))?;
+        let request = &CallbackRequest::from(unsafe { &*request });
+        let result = block_on(with_token_refresh(&token, |token| async move {
+            callback(request, &token).await
+        }))?;
         let ffi_response = FfiCallbackResponse::try_from(result)?;
         unsafe {
             (*out_response).status = ffi_response.status;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄上传音频文件进行转写
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `filepath`: 音频文件路径（C 字符串）
+/// - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+/// - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `out_result`: 输出上传结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_with_client(
+    client: *const DianyaClient,
+    filepath: *const c_char,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: *const c_char,
+    out_result: *mut FfiUploadResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+        let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+
+        let (filepath, model) = (filepath.as_str(), model.as_str());
+        let result = client.block_on(false, |token| async move {
+            let model = model_type_from_str(model)?;
+            track_upload(
+                filepath,
+                upload(filepath, transcribe_only, short_asr, model, &token),
+            )
+            .await
+        })?;
+
+        let ffi_result = FfiUploadResponse::try_from(result)?;
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄获取转写任务状态
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（可为 NULL，如果提供 share_id）
+/// - `share_id`: 分享链接ID（可为 NULL，如果提供 task_id）
+/// - `out_status`: 输出状态结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_get_status_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    share_id: *const c_char,
+    out_status: *mut FfiTranscribeStatus,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_status.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let task_id_opt = if task_id.is_null() {
+            None
+        } else {
+            Some(parse_c_str(task_id, |s| Ok(s.to_string()))?)
+        };
+        let share_id_opt = if share_id.is_null() {
+            None
+        } else {
+            Some(parse_c_str(share_id, |s| Ok(s.to_string()))?)
+        };
+
+        let (task_id, share_id) = (task_id_opt.as_deref(), share_id_opt.as_deref());
+        let status_response = client.block_on(true, |token| async move {
+            track(Operation::GetStatus, status(task_id, share_id, &token)).await
+        })?;
+
+        let ffi_status = FfiTranscribeStatus::try_from(status_response)?;
+        unsafe {
+            *out_status = ffi_status;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄导出转写内容或总结内容
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `result_data`: 输出二进制数据的缓冲区指针
+/// - `result_len`: 输入时为缓冲区大小，输出时为实际长度
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    result_data: *mut u8,
+    result_len: *mut usize,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if result_data.is_null() || result_len.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let args = parse_export_args(task_id, export_type, export_format)?;
+
+        let args = &args;
+        let data = client.block_on(true, |token| async move { args.export(&token).await })?;
+        let len = data.len();
+        let buffer_size = unsafe { *result_len };
+
+        if len > buffer_size {
+            unsafe { *result_len = len };
+            return Err(FfiError::buffer_too_small(len));
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), result_data, len);
+            *result_len = len;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄导出转写内容或总结内容，数据由本库分配
+///
+/// 成功后需调用 `transcribe_ffi_free_bytes` 释放 `out_bytes`。
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `out_bytes`: 输出二进制数据结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_bytes_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    out_bytes: *mut FfiBytes,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_bytes.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let args = parse_export_args(task_id, export_type, export_format)?;
+
+        let args = &args;
+        let data = client.block_on(true, |token| async move { args.export(&token).await })?;
+        unsafe {
+            *out_bytes = FfiBytes::from(data);
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄翻译文本
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `text`: 要翻译的文本（C 字符串）
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `out_result`: 输出文本翻译结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_text_with_client(
+    client: *const DianyaClient,
+    text: *const c_char,
+    target_lang: *const c_char,
+    out_result: *mut FfiTextTranslator,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let text = parse_c_str(text, |s| Ok(s.to_string()))?;
+        let lang = parse_language(target_lang)?;
+
+        let text = text.as_str();
+        let result = client.block_on(false, |token| async move {
+            track(Operation::TranslateText, translate_text(text, lang, &token)).await
+        })?;
+        let result = FfiTextTranslator::try_from(result)?;
+        unsafe {
+            *out_result = result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄翻译 utterances 列表
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `utterances`: Utterance 数组指针
+/// - `utterances_len`: Utterance 数组长度
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `out_result`: 输出 utterance 翻译结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_utterance_with_client(
+    client: *const DianyaClient,
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    target_lang: *const c_char,
+    out_result: *mut FfiUtteranceTranslator,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if utterances.is_null() || utterances_len == 0 || out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid input".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let utterances = unsafe { std::slice::from_raw_parts(utterances, utterances_len) };
+        let lang = parse_language(target_lang)?;
+
+        let result = client.block_on(false, |token| {
+            let utterances: Vec<Utterance> = utterances.iter().map(Utterance::from).collect();
+            async move {
+                track(
+                    Operation::TranslateUtterance,
+                    translate_utterance(utterances, lang, &token),
+                )
+                .await
+            }
+        })?;
+        let result = FfiUtteranceTranslator::try_from(result)?;
+        unsafe {
+            *out_result = result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄获取转写任务的翻译结果
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `out_result`: 输出转写翻译结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_transcribe_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    target_lang: *const c_char,
+    out_result: *mut FfiTranscribeTranslator,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("out_result is null".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+        let lang = parse_language(target_lang)?;
+
+        let task_id = task_id.as_str();
+        let result = client.block_on(true, |token| async move {
+            track(
+                Operation::TranslateTranscribe,
+                translate_transcribe(task_id, lang, &token),
+            )
+            .await
+        })?;
+        let result = FfiTranscribeTranslator::try_from(result)?;
+        unsafe {
+            *out_result = result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄获取转写分享链接
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `expiration_day`: 过期天数（0 表示使用默认值 7 天）
+/// - `out_link`: 输出分享链接结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_get_share_link_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    expiration_day: i32,
+    out_link: *mut FfiShareLink,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_link.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+        let expiration_opt = if expiration_day == 0 {
+            None
+        } else {
+            Some(expiration_day)
+        };
+
+        let task_id = task_id.as_str();
+        let link = client.block_on(true, |token| async move {
+            get_share_link(task_id, expiration_opt, &token).await
+        })?;
+        let ffi_link = FfiShareLink::try_from(link)?;
+        unsafe {
+            *out_link = ffi_link;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄创建总结任务
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `utterances`: Utterance 数组指针
+/// - `utterances_len`: Utterance 数组长度
+/// - `out_summary`: 输出总结任务信息结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_create_summary_with_client(
+    client: *const DianyaClient,
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    out_summary: *mut FfiSummaryCreator,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if utterances.is_null() || utterances_len == 0 || out_summary.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let utterances = unsafe { std::slice::from_raw_parts(utterances, utterances_len) };
+        let response = client.block_on(false, |token| {
+            let utterances: Vec<Utterance> = utterances.iter().map(Utterance::from).collect();
+            async move { create_summary(utterances, &token).await }
+        })?;
+        let ffi_summary = FfiSummaryCreator::try_from(response)?;
+        unsafe {
+            *out_summary = ffi_summary;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄处理转写任务状态回调（服务器端使用）
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `request`: 回调请求结构体指针
+/// - `out_response`: 输出回调响应结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_callback_with_client(
+    client: *const DianyaClient,
+    request: *const FfiCallbackRequest,
+    out_response: *mut FfiCallbackResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if request.is_null() || out_response.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let request = &CallbackRequest::from(unsafe { &*request });
+        let result = client.block_on(
+            false,
+            |token| async move { callback(request, &token).await },
+        )?;
+        let ffi_response = FfiCallbackResponse::try_from(result)?;
+        unsafe {
+            *out_response = ffi_response;
+        }
+
+        Ok::<(), FfiError>(())
     })
 }
@@ -0,0 +1,769 @@
+use std::ffi::*;
+use transcribe::{
+    transcribe::{close_session, create_session, status, upload},
+    translate::{translate_text, translate_transcribe, translate_utterance},
+    types::Utterance,
+};
+
+use crate::{
+    error::FfiError,
+    metrics::{track, track_upload, Operation},
+    operation::{deliver, deliver_error, spawn_async, FfiOperation, OpOutput, UserData},
+    retry::{with_retry, RetryPolicy},
+    token::with_token_refresh,
+    transcribe_api::parse_export_args,
+    transcribe_ffi_free_session_creator, transcribe_ffi_free_session_ender,
+    transcribe_ffi_free_text_translator, transcribe_ffi_free_transcribe_status,
+    transcribe_ffi_free_transcribe_translator, transcribe_ffi_free_upload_response,
+    transcribe_ffi_free_utterance_translator,
+    utils::*,
+    FfiSessionCreator, FfiSessionEnder, FfiTextTranslator, FfiTranscribeStatus,
+    FfiTranscribeTranslator, FfiUploadResponse, FfiUtterance, FfiUtteranceTranslator,
+};
+use common::Error;
+
+/// 上传完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiUploadCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiUploadResponse,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 获取状态完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiStatusCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiTranscribeStatus,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 导出完成回调
+///
+/// `data` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiExportCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        data: *const u8,
+        data_len: usize,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 文本翻译完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiTextTranslatorCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiTextTranslator,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// Utterance 翻译完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiUtteranceTranslatorCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiUtteranceTranslator,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 转写翻译完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiTranscribeTranslatorCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiTranscribeTranslator,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 创建会话完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiSessionCreatorCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiSessionCreator,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 关闭会话完成回调
+///
+/// `result` 和 `error` 只在回调期间有效，回调返回后由本库释放。
+/// 回调在 runtime 线程上执行，不能在其中调用同步接口（返回 `InvalidInput` 错误），需要时请使用异步接口
+pub type FfiSessionEnderCallback = Option<
+    extern "C" fn(
+        op_id: u64,
+        code: c_int,
+        result: *const FfiSessionEnder,
+        error: *const FfiError,
+        user_data: *mut c_void,
+    ),
+>;
+
+/// 异步上传音频文件进行转写
+///
+/// 立即返回，上传完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `filepath`: 音频文件路径（C 字符串）
+/// - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+/// - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_async(
+    filepath: *const c_char,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: *const c_char,
+    token: *const c_char,
+    callback: FfiUploadCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let (filepath, model) = (filepath.as_str(), model.as_str());
+                with_token_refresh(&token, |token| async move {
+                    let model = model_type_from_str(model)?;
+                    track_upload(
+                        filepath,
+                        upload(filepath, transcribe_only, short_asr, model, &token),
+                    )
+                    .await
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result =
+                    result.and_then(|r| FfiUploadResponse::try_from(r).map_err(FfiError::from));
+                deliver(result, transcribe_ffi_free_upload_response, |code, r, e| {
+                    callback(op_id, code, r, e, user_data.get())
+                });
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 启动可取消的上传操作
+///
+/// 立即返回操作句柄，可通过 `transcribe_ffi_op_wait` / `transcribe_ffi_op_poll` 查询进度，
+/// 通过 `transcribe_ffi_op_cancel` 取消，结束后用 `transcribe_ffi_op_upload_result` 获取结果。
+/// 句柄需调用 `transcribe_ffi_op_free` 释放。
+///
+/// # 参数
+/// - `filepath`: 音频文件路径（C 字符串）
+/// - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+/// - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `token`: Bearer token（C 字符串）
+/// - `out_op`: 输出操作句柄指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_start(
+    filepath: *const c_char,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: *const c_char,
+    token: *const c_char,
+    out_op: *mut *mut FfiOperation,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_op.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let op = FfiOperation::spawn(async move {
+            let (filepath, model) = (filepath.as_str(), model.as_str());
+            with_token_refresh(&token, |token| async move {
+                let model = model_type_from_str(model)?;
+                track_upload(
+                    filepath,
+                    upload(filepath, transcribe_only, short_asr, model, &token),
+                )
+                .await
+            })
+            .await
+            .map(OpOutput::Upload)
+        })?;
+
+        unsafe {
+            *out_op = Box::into_raw(Box::new(op));
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步获取转写任务状态
+///
+/// 立即返回，请求完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `task_id`: 任务ID（可为 NULL，如果提供 share_id）
+/// - `share_id`: 分享链接ID（可为 NULL，如果提供 task_id）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_get_status_async(
+    task_id: *const c_char,
+    share_id: *const c_char,
+    token: *const c_char,
+    callback: FfiStatusCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let task_id_opt = if task_id.is_null() {
+            None
+        } else {
+            Some(parse_c_str(task_id, |s| Ok(s.to_string()))?)
+        };
+        let share_id_opt = if share_id.is_null() {
+            None
+        } else {
+            Some(parse_c_str(share_id, |s| Ok(s.to_string()))?)
+        };
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+        let policy = RetryPolicy::global();
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let (task_id, share_id) = (task_id_opt.as_deref(), share_id_opt.as_deref());
+                with_retry(&policy, || {
+                    with_token_refresh(&token, |token| async move {
+                        track(Operation::GetStatus, status(task_id, share_id, &token)).await
+                    })
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result =
+                    result.and_then(|r| FfiTranscribeStatus::try_from(r).map_err(FfiError::from));
+                deliver(
+                    result,
+                    transcribe_ffi_free_transcribe_status,
+                    |code, r, e| callback(op_id, code, r, e, user_data.get()),
+                );
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步导出转写内容或总结内容
+///
+/// 立即返回，导出完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_async(
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    token: *const c_char,
+    callback: FfiExportCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let args = parse_export_args(task_id, export_type, export_format)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+        let policy = RetryPolicy::global();
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let args = &args;
+                with_retry(&policy, || {
+                    with_token_refresh(&token, |token| async move { args.export(&token).await })
+                })
+                .await
+            },
+            move |op_id, result| match result {
+                Ok(data) => callback(
+                    op_id,
+                    0,
+                    data.as_ptr(),
+                    data.len(),
+                    std::ptr::null(),
+                    user_data.get(),
+                ),
+                Err(e) => deliver_error(e, |code, error| {
+                    callback(op_id, code, std::ptr::null(), 0, error, user_data.get())
+                }),
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 启动可取消的导出操作
+///
+/// 立即返回操作句柄，结束后用 `transcribe_ffi_op_export_result` 获取数据。
+/// 句柄需调用 `transcribe_ffi_op_free` 释放。
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `export_type`: 导出类型字符串（"transcript", "overview", "summary"）
+/// - `export_format`: 导出格式字符串（"pdf", "txt", "docx"）
+/// - `token`: Bearer token（C 字符串）
+/// - `out_op`: 输出操作句柄指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_export_start(
+    task_id: *const c_char,
+    export_type: *const c_char,
+    export_format: *const c_char,
+    token: *const c_char,
+    out_op: *mut *mut FfiOperation,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_op.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let args = parse_export_args(task_id, export_type, export_format)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let op = FfiOperation::spawn(async move {
+            let args = &args;
+            let policy = RetryPolicy::global();
+            with_retry(&policy, || {
+                with_token_refresh(&token, |token| async move { args.export(&token).await })
+            })
+            .await
+            .map(OpOutput::Export)
+        })?;
+
+        unsafe {
+            *out_op = Box::into_raw(Box::new(op));
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步翻译文本
+///
+/// 立即返回，翻译完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `text`: 要翻译的文本（C 字符串）
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_text_async(
+    text: *const c_char,
+    target_lang: *const c_char,
+    token: *const c_char,
+    callback: FfiTextTranslatorCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let text = parse_c_str(text, |s| Ok(s.to_string()))?;
+        let lang = parse_language(target_lang)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let text = text.as_str();
+                with_token_refresh(&token, |token| async move {
+                    track(Operation::TranslateText, translate_text(text, lang, &token)).await
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result =
+                    result.and_then(|r| FfiTextTranslator::try_from(r).map_err(FfiError::from));
+                deliver(result, transcribe_ffi_free_text_translator, |code, r, e| {
+                    callback(op_id, code, r, e, user_data.get())
+                });
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步翻译 utterances 列表
+///
+/// 立即返回，翻译完成后在 runtime 线程上调用 `callback`。
+/// `utterances` 在函数返回前已被复制，调用方可立即释放。
+///
+/// # 参数
+/// - `utterances`: Utterance 数组指针
+/// - `utterances_len`: Utterance 数组长度
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_utterance_async(
+    utterances: *const FfiUtterance,
+    utterances_len: usize,
+    target_lang: *const c_char,
+    token: *const c_char,
+    callback: FfiUtteranceTranslatorCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if utterances.is_null() || utterances_len == 0 || out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid input".to_string()).into());
+        }
+
+        let utterances: Vec<Utterance> = unsafe {
+            std::slice::from_raw_parts(utterances, utterances_len)
+                .iter()
+                .map(Utterance::from)
+                .collect()
+        };
+        let lang = parse_language(target_lang)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                with_token_refresh(&token, |token| {
+                    let utterances = utterances
+                        .iter()
+                        .map(|u| Utterance {
+                            start_time: u.start_time,
+                            end_time: u.end_time,
+                            speaker: u.speaker,
+                            text: u.text.clone(),
+                        })
+                        .collect();
+                    async move {
+                        track(
+                            Operation::TranslateUtterance,
+                            translate_utterance(utterances, lang, &token),
+                        )
+                        .await
+                    }
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result = result
+                    .and_then(|r| FfiUtteranceTranslator::try_from(r).map_err(FfiError::from));
+                deliver(
+                    result,
+                    transcribe_ffi_free_utterance_translator,
+                    |code, r, e| callback(op_id, code, r, e, user_data.get()),
+                );
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步获取转写任务的翻译结果
+///
+/// 立即返回，请求完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `target_lang`: 目标语言代码（"zh", "en", "ja", "ko", "fr", "de"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_translate_transcribe_async(
+    task_id: *const c_char,
+    target_lang: *const c_char,
+    token: *const c_char,
+    callback: FfiTranscribeTranslatorCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+        let lang = parse_language(target_lang)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+        let policy = RetryPolicy::global();
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let task_id = task_id.as_str();
+                with_retry(&policy, || {
+                    with_token_refresh(&token, |token| async move {
+                        track(
+                            Operation::TranslateTranscribe,
+                            translate_transcribe(task_id, lang, &token),
+                        )
+                        .await
+                    })
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result = result
+                    .and_then(|r| FfiTranscribeTranslator::try_from(r).map_err(FfiError::from));
+                deliver(
+                    result,
+                    transcribe_ffi_free_transcribe_translator,
+                    |code, r, e| callback(op_id, code, r, e, user_data.get()),
+                );
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步创建实时转写会话
+///
+/// 立即返回，会话创建完成后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `token`: Bearer token（C 字符串）
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_create_session_async(
+    model: *const c_char,
+    token: *const c_char,
+    callback: FfiSessionCreatorCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let user_data = UserData::new(user_data);
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let model = model.as_str();
+                with_token_refresh(&token, |token| async move {
+                    create_session(model_type_from_str(model)?, &token).await
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result =
+                    result.and_then(|r| FfiSessionCreator::try_from(r).map_err(FfiError::from));
+                deliver(result, transcribe_ffi_free_session_creator, |code, r, e| {
+                    callback(op_id, code, r, e, user_data.get())
+                });
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 异步关闭实时转写会话
+///
+/// 立即返回，会话关闭后在 runtime 线程上调用 `callback`
+///
+/// # 参数
+/// - `task_id`: 任务ID（C 字符串）
+/// - `token`: Bearer token（C 字符串）
+/// - `timeout`: 超时时间（秒），0 表示使用默认值 30 秒
+/// - `callback`: 完成回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_op_id`: 输出操作ID
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示操作已启动）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_close_session_async(
+    task_id: *const c_char,
+    token: *const c_char,
+    timeout: u64,
+    callback: FfiSessionEnderCallback,
+    user_data: *mut c_void,
+    out_op_id: *mut u64,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let callback =
+            callback.ok_or_else(|| Error::InvalidInput("callback is null".to_string()))?;
+        if out_op_id.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let timeout_opt = if timeout == 0 { None } else { Some(timeout) };
+        let user_data = UserData::new(user_data);
+
+        spawn_async(
+            unsafe { &mut *out_op_id },
+            async move {
+                let task_id = task_id.as_str();
+                with_token_refresh(&token, |token| async move {
+                    close_session(task_id, &token, timeout_opt).await
+                })
+                .await
+            },
+            move |op_id, result| {
+                let result =
+                    result.and_then(|r| FfiSessionEnder::try_from(r).map_err(FfiError::from));
+                deliver(result, transcribe_ffi_free_session_ender, |code, r, e| {
+                    callback(op_id, code, r, e, user_data.get())
+                });
+            },
+        )?;
+
+        Ok::<(), FfiError>(())
+    })
+}
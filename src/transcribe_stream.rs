@@ -1,13 +1,22 @@
 use std::ffi::*;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::Instant;
 
 use stream_cancel::Valved;
 use tokio_stream::{Stream, StreamExt};
 use transcribe::transcribe::{close_session, create_session, TranscribeWs};
 use tungstenite::{Message, Utf8Bytes};
 
-use crate::{error::FfiError, runtime::get_runtime, utils::*, FfiSessionCreator, FfiSessionEnder};
+use crate::{
+    client::DianyaClient,
+    error::{ErrorCode, FfiError},
+    metrics::{record_call, record_message, track, Operation},
+    runtime::block_on,
+    token::with_token_refresh,
+    utils::*,
+    FfiSessionCreator, FfiSessionEnder,
+};
 
 /// WebSocket 连接信息，包含连接实例和订阅流
 /// 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
@@ -15,6 +24,19 @@ use crate::{error::FfiError, runtime::get_runtime, utils::*, FfiSessionCreator,
 pub struct TranscribeStream {
     ws: Arc<Mutex<TranscribeWs>>,
     stream: Arc<Mutex<Valved<Pin<Box<dyn Stream<Item = Utf8Bytes> + Send>>>>>,
+    /// 因缓冲区不足未能取出的消息，下次接收时优先返回
+    pending: Mutex<Option<Utf8Bytes>>,
+}
+
+/// 所有已创建的 WebSocket 连接，用于关闭 runtime 时统一停止
+static OPEN_STREAMS: Mutex<Vec<Weak<Mutex<TranscribeWs>>>> = Mutex::new(Vec::new());
+
+/// 停止所有未释放的 WebSocket 连接
+pub(crate) fn close_all_streams() {
+    let streams = std::mem::take(&mut *OPEN_STREAMS.lock().unwrap_or_else(PoisonError::into_inner));
+    for ws in streams.iter().filter_map(Weak::upgrade) {
+        ws.lock().unwrap_or_else(PoisonError::into_inner).stop();
+    }
 }
 
 /// 创建实时转写会话
@@ -35,15 +57,19 @@ pub extern "C" fn transcribe_ffi_create_session(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_session.is_null() {
-            return Err(common::Error::InvalidInput(
-                "Invalid output parameters".to_string(),
-            ));
+            return Err(
+                common::Error::InvalidInput("Invalid output parameters".to_string()).into(),
+            );
         }
 
-        let model = parse_model_type(model)?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
 
-        let session = get_runtime().block_on(create_session(model, &token))?;
+        let model = model.as_str();
+        let session = block_on(with_token_refresh(&token, |token| async move {
+            create_session(model_type_from_str(model)?, &token).await
+        }))?;
         let ffi_session = FfiSessionCreator::try_from(session)?;
 
         unsafe {
@@ -53,7 +79,7 @@ pub extern "C" fn transcribe_ffi_create_session(
             (*out_session).max_time = ffi_session.max_time;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -77,16 +103,19 @@ pub extern "C" fn transcribe_ffi_close_session(
 ) -> c_int {
     ffi_execute(out_error, || {
         if out_result.is_null() {
-            return Err(common::Error::InvalidInput(
-                "Invalid output parameters".to_string(),
-            ));
+            return Err(
+                common::Error::InvalidInput("Invalid output parameters".to_string()).into(),
+            );
         }
 
         let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
         let token = parse_c_str(token, |s| Ok(s.to_string()))?;
         let timeout_opt = if timeout == 0 { None } else { Some(timeout) };
 
-        let result = get_runtime().block_on(close_session(&task_id, &token, timeout_opt))?;
+        let task_id = task_id.as_str();
+        let result = block_on(with_token_refresh(&token, |token| async move {
+            close_session(task_id, &token, timeout_opt).await
+        }))?;
         let ffi_result = FfiSessionEnder::try_from(result)?;
 
         unsafe {
@@ -98,7 +127,91 @@ pub extern "C" fn transcribe_ffi_close_session(
             (*out_result).message = ffi_result.message;
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄创建实时转写会话
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `out_session`: 输出的会话信息结构体指针
+///
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_create_session_with_client(
+    client: *const DianyaClient,
+    model: *const c_char,
+    out_session: *mut FfiSessionCreator,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_session.is_null() {
+            return Err(
+                common::Error::InvalidInput("Invalid output parameters".to_string()).into(),
+            );
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+
+        let model = model.as_str();
+        let session = client.block_on(false, |token| async move {
+            create_session(model_type_from_str(model)?, &token).await
+        })?;
+        let ffi_session = FfiSessionCreator::try_from(session)?;
+
+        unsafe {
+            *out_session = ffi_session;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 使用客户端句柄关闭实时转写会话
+///
+/// # 参数
+/// - `client`: 客户端句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `timeout`: 超时时间（秒），0 表示使用默认值 30 秒
+/// - `out_result`: 输出的会话关闭结果结构体指针
+///
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_close_session_with_client(
+    client: *const DianyaClient,
+    task_id: *const c_char,
+    timeout: u64,
+    out_result: *mut FfiSessionEnder,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(
+                common::Error::InvalidInput("Invalid output parameters".to_string()).into(),
+            );
+        }
+        let client = unsafe { DianyaClient::from_ptr(client)? };
+
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+        let timeout_opt = if timeout == 0 { None } else { Some(timeout) };
+
+        let task_id = task_id.as_str();
+        let result = client.block_on(false, |token| async move {
+            close_session(task_id, &token, timeout_opt).await
+        })?;
+        let ffi_result = FfiSessionEnder::try_from(result)?;
+
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -123,11 +236,19 @@ pub extern "C" fn transcribe_ffi_ws_create(
         }
 
         let session_id = parse_c_str(session_id, |s| Ok(s.to_string()))?;
+        log::debug!("Creating WebSocket for session {}", session_id);
         let mut ws = TranscribeWs::new(&session_id);
         let stream = ws.subscribe()?;
+        let ws = Arc::new(Mutex::new(ws));
+        {
+            let mut streams = OPEN_STREAMS.lock().unwrap_or_else(PoisonError::into_inner);
+            streams.retain(|ws| ws.strong_count() > 0);
+            streams.push(Arc::downgrade(&ws));
+        }
         let conn = Box::new(TranscribeStream {
-            ws: Arc::new(Mutex::new(ws)),
+            ws,
             stream: Arc::new(Mutex::new(stream)),
+            pending: Mutex::new(None),
         });
 
         unsafe {
@@ -152,17 +273,16 @@ pub extern "C" fn transcribe_ffi_ws_start(
 ) -> c_int {
     ffi_execute(out_error, || {
         if handle.is_null() {
-            return Err(common::Error::InvalidInput("Invalid handle".to_string()));
+            return Err(common::Error::InvalidInput("Invalid handle".to_string()).into());
         }
 
         unsafe {
-            let mut guard = (*handle).ws.lock().map_err(|e| {
-                common::Error::OtherError(format!("Failed to acquire mutex lock: {}", e))
-            })?;
-            get_runtime().block_on(guard.start())?;
+            let mut guard = (*handle).ws.lock().map_err(FfiError::poisoned)?;
+            block_on(guard.start())?;
+            log::debug!("WebSocket started");
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -182,21 +302,23 @@ pub extern "C" fn transcribe_ffi_ws_write_txt(
 ) -> c_int {
     ffi_execute(out_error, || {
         if handle.is_null() || text.is_null() {
-            return Err(common::Error::InvalidInput(
-                "Invalid input parameters".to_string(),
-            ));
+            return Err(common::Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         let text_str = parse_c_str(text, |s| Ok(s.to_string()))?;
+        log::trace!("WebSocket sending text frame ({} bytes)", text_str.len());
 
         unsafe {
-            let mut guard = (*handle).ws.lock().map_err(|e| {
-                common::Error::OtherError(format!("Failed to acquire mutex lock: {}", e))
-            })?;
-            get_runtime().block_on(guard.write(Message::Text(text_str.into())))?;
+            let mut guard = (*handle).ws.lock().map_err(FfiError::poisoned)?;
+            let len = text_str.len();
+            block_on(track(
+                Operation::WsWriteTxt,
+                guard.write(Message::Text(text_str.into())),
+            ))?;
+            record_message(Operation::WsWriteTxt, true, len);
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -218,22 +340,23 @@ pub extern "C" fn transcribe_ffi_ws_write_bytes(
 ) -> c_int {
     ffi_execute(out_error, || {
         if handle.is_null() || data.is_null() || data_len == 0 {
-            return Err(common::Error::InvalidInput(
-                "Invalid input parameters".to_string(),
-            ));
+            return Err(common::Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
         // 复制数据
         let bytes: Vec<u8> = unsafe { std::slice::from_raw_parts(data, data_len).to_vec() };
+        log::trace!("WebSocket sending binary frame ({} bytes)", data_len);
 
         unsafe {
-            let mut guard = (*handle).ws.lock().map_err(|e| {
-                common::Error::OtherError(format!("Failed to acquire mutex lock: {}", e))
-            })?;
-            get_runtime().block_on(guard.write(Message::Binary(bytes.into())))?;
+            let mut guard = (*handle).ws.lock().map_err(FfiError::poisoned)?;
+            block_on(track(
+                Operation::WsWriteBytes,
+                guard.write(Message::Binary(bytes.into())),
+            ))?;
+            record_message(Operation::WsWriteBytes, true, data_len);
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -251,22 +374,24 @@ pub extern "C" fn transcribe_ffi_ws_stop(
 ) -> c_int {
     ffi_execute(out_error, || {
         if handle.is_null() {
-            return Err(common::Error::InvalidInput("Invalid handle".to_string()));
+            return Err(common::Error::InvalidInput("Invalid handle".to_string()).into());
         }
 
         unsafe {
-            let mut ws = (*handle).ws.lock().map_err(|e| {
-                common::Error::OtherError(format!("Failed to acquire mutex lock: {}", e))
-            })?;
+            let mut ws = (*handle).ws.lock().map_err(FfiError::poisoned)?;
             ws.stop();
         }
+        log::debug!("WebSocket stopped");
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
 /// 接收 WebSocket 消息（轮询方式）
 ///
+/// 缓冲区不足时返回 `BufferTooSmall`，所需大小（含结尾的 NUL）写入 `message_len`，
+/// 消息保留在句柄中，下次调用时优先返回。
+///
 /// # 参数
 /// - `handle`: WebSocket 句柄
 /// - `message_json`: 输出消息 JSON 的缓冲区指针
@@ -274,7 +399,7 @@ pub extern "C" fn transcribe_ffi_ws_stop(
 /// - `timeout_ms`: 超时时间（毫秒），0 表示立即返回
 ///
 /// # 返回
-/// 错误码（0 表示成功）
+/// 错误码（0 表示成功，超时时 `message_len` 为 0；消息流已结束时为 `StreamClosed`）
 #[no_mangle]
 pub extern "C" fn transcribe_ffi_ws_receive(
     handle: *mut TranscribeStream,
@@ -285,58 +410,76 @@ pub extern "C" fn transcribe_ffi_ws_receive(
 ) -> c_int {
     ffi_execute(out_error, || {
         if handle.is_null() || message_json.is_null() || message_len.is_null() {
-            return Err(common::Error::InvalidInput(
-                "Invalid input parameters".to_string(),
-            ));
+            return Err(common::Error::InvalidInput("Invalid input parameters".to_string()).into());
         }
 
-        let message = unsafe {
-            let mut guard = (*handle).stream.lock().map_err(|e| {
-                common::Error::OtherError(format!("Failed to acquire stream mutex lock: {}", e))
-            })?;
-
-            let result = if timeout_ms == 0 {
-                get_runtime().block_on(async { guard.next().await })
-            } else {
-                // 带超时
-                get_runtime().block_on(async {
-                    use tokio::time::{sleep, Duration};
-                    tokio::select! {
-                        msg = guard.next() => msg,
-                        _ = sleep(Duration::from_millis(timeout_ms)) => None,
-                    }
-                })
-            };
-            result
+        let started = Instant::now();
+        let handle = unsafe { &*handle };
+        let mut pending = handle.pending.lock().map_err(FfiError::poisoned)?;
+        let message = match pending.take() {
+            Some(text) => Some(Some(text)),
+            None => {
+                let mut guard = handle.stream.lock().map_err(FfiError::poisoned)?;
+
+                // 外层 None 表示超时，内层 None 表示消息流已结束
+                if timeout_ms == 0 {
+                    block_on(async { Ok::<_, FfiError>(Some(guard.next().await)) })?
+                } else {
+                    // 带超时
+                    block_on(async {
+                        use tokio::time::{sleep, Duration};
+                        Ok::<_, FfiError>(tokio::select! {
+                            msg = guard.next() => Some(msg),
+                            _ = sleep(Duration::from_millis(timeout_ms)) => None,
+                        })
+                    })?
+                }
+            }
         };
 
         match message {
-            Some(text) => {
-                let text_str = text.to_string();
-                let len = text_str.len();
+            Some(Some(text)) => {
+                let len = text.len();
+                log::trace!("WebSocket received message ({} bytes)", len);
                 let buffer_size = unsafe { *message_len };
 
                 if len + 1 > buffer_size {
+                    record_call(
+                        Operation::WsReceive,
+                        started.elapsed(),
+                        Some(ErrorCode::BufferTooSmall),
+                    );
                     unsafe { *message_len = len + 1 };
-                    return Err(common::Error::OtherError(format!(
-                        "Buffer too small, need {} bytes",
-                        len + 1
-                    )));
+                    *pending = Some(text);
+                    return Err(FfiError::buffer_too_small(len + 1));
                 }
+                record_call(Operation::WsReceive, started.elapsed(), None);
+                record_message(Operation::WsReceive, false, len);
 
                 unsafe {
-                    let bytes = text_str.as_bytes();
+                    let bytes = text.as_bytes();
                     std::ptr::copy_nonoverlapping(bytes.as_ptr(), message_json as *mut u8, len);
                     *message_json.add(len) = 0;
                     *message_len = len;
                 }
             }
+            Some(None) => {
+                log::debug!("WebSocket stream closed");
+                record_call(
+                    Operation::WsReceive,
+                    started.elapsed(),
+                    Some(ErrorCode::StreamClosed),
+                );
+                unsafe { *message_len = 0 };
+                return Err(FfiError::stream_closed());
+            }
+            // 轮询超时，不计入统计
             None => unsafe {
                 *message_len = 0;
             },
         }
 
-        Ok(())
+        Ok::<(), FfiError>(())
     })
 }
 
@@ -1,3 +1,5 @@
+mod bytes;
+pub use bytes::*;
 mod export;
 pub use export::*;
 mod share;
@@ -37,6 +39,17 @@ impl From<FfiUtterance> for Utterance {
     }
 }
 
+impl From<&FfiUtterance> for Utterance {
+    fn from(v: &FfiUtterance) -> Self {
+        Self {
+            start_time: v.start_time,
+            end_time: v.end_time,
+            speaker: v.speaker,
+            text: unsafe { CStr::from_ptr(v.text).to_string_lossy().to_string() },
+        }
+    }
+}
+
 impl TryFrom<Utterance> for FfiUtterance {
     type Error = Error;
     fn try_from(v: Utterance) -> Result<Self, Self::Error> {
@@ -59,6 +72,20 @@ pub(crate) unsafe fn free_c_string(p: *mut *mut c_char) {
     }
 }
 
+/// 释放本库分配的 C 字符串
+///
+/// # 参数
+/// - `s`: C 字符串指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_string(s: *mut c_char) {
+    if s.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = CString::from_raw(s);
+    }
+}
+
 /// 释放字符串数组
 pub(crate) unsafe fn free_string_array(ptr: *mut *mut *mut c_char, len: *mut usize) {
     array_call(ptr, len, |ptr, len| {
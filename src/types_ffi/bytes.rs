@@ -0,0 +1,33 @@
+/// 由本库分配的二进制数据
+#[repr(C)]
+pub struct FfiBytes {
+    pub ptr: *mut u8,
+    pub len: usize,
+}
+
+impl From<Vec<u8>> for FfiBytes {
+    fn from(v: Vec<u8>) -> Self {
+        let len = v.len();
+        let ptr = if len > 0 {
+            Box::into_raw(v.into_boxed_slice()) as *mut u8
+        } else {
+            std::ptr::null_mut()
+        };
+        Self { ptr, len }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_bytes(b: *mut FfiBytes) {
+    if b.is_null() {
+        return;
+    }
+    unsafe {
+        if !(*b).ptr.is_null() && (*b).len > 0 {
+            let slice = std::ptr::slice_from_raw_parts_mut((*b).ptr, (*b).len);
+            let _ = Box::from_raw(slice);
+        }
+        (*b).ptr = std::ptr::null_mut();
+        (*b).len = 0;
+    }
+}
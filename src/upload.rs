@@ -0,0 +1,440 @@
+use std::collections::hash_map::RandomState;
+use std::ffi::*;
+use std::fs::{DirBuilder, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use common::Error;
+use transcribe::transcribe::{upload, UploadResponse};
+
+use crate::{
+    error::FfiError, metrics::track_upload, runtime::block_on, token::with_token_refresh, utils::*,
+    FfiUploadResponse,
+};
+
+/// 上传数据读取回调
+///
+/// 向 `buf` 写入最多 `buf_len` 字节，返回写入的字节数；返回 0 表示数据结束，
+/// 返回负值将中止上传
+pub type FfiReadCallback =
+    Option<extern "C" fn(buf: *mut u8, buf_len: usize, user_data: *mut c_void) -> isize>;
+
+/// 上传选项
+///
+/// 调用方须先用 `transcribe_ffi_upload_options_init` 初始化（或将 `struct_size` 设为
+/// `sizeof(FfiUploadOptions)` 并将其余字段清零）。新字段只会追加在末尾，使用旧版本头文件
+/// 编译的调用方传入较小的 `struct_size`，未覆盖的字段按默认值处理。
+#[repr(C)]
+pub struct FfiUploadOptions {
+    /// 结构体大小（字节）
+    pub struct_size: usize,
+    /// 模型类型字符串（"speed", "quality", "quality_v2"），不能为 null
+    pub model: *const c_char,
+    /// 是否仅转写
+    pub transcribe_only: bool,
+    /// 是否使用一句话转写模式
+    pub short_asr: bool,
+}
+
+/// 解析后的上传选项
+pub(crate) struct UploadOptions {
+    pub model: String,
+    pub transcribe_only: bool,
+    pub short_asr: bool,
+}
+
+impl UploadOptions {
+    /// 读取调用方提供的选项，只读取 `struct_size` 覆盖的字段
+    pub unsafe fn read(options: *const FfiUploadOptions) -> Result<Self, Error> {
+        let options = read_sized_struct(
+            options,
+            std::mem::offset_of!(FfiUploadOptions, transcribe_only),
+        )?
+        .ok_or_else(|| Error::InvalidInput("options is null".to_string()))?;
+
+        let model = parse_c_str(options.model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+
+        Ok(Self {
+            model,
+            transcribe_only: options.transcribe_only,
+            short_asr: options.short_asr,
+        })
+    }
+}
+
+/// 每次从读取回调读取的最大字节数
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 未提供文件名时使用的文件名
+const DEFAULT_FILENAME: &str = "audio";
+
+/// 临时目录计数器，与随机数一起生成目录名
+static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+/// 创建临时目录时遇到同名目录的最大重试次数
+const TEMP_DIR_ATTEMPTS: u32 = 16;
+
+/// 生成不可预测的临时目录名
+fn random_temp_name() -> String {
+    // RandomState 每次以随机密钥初始化，哈希结果不可预测
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    hasher.write_u64(NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed));
+    format!("dianyaapi-ffi-{:016x}", hasher.finish())
+}
+
+/// 创建仅当前用户可访问的新目录，目录已存在时失败（不跟随他人预先创建的目录或符号链接）
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    let mut builder = DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path)
+}
+
+/// 上传用的临时文件，释放时删除
+///
+/// SDK 只支持从文件路径上传，内存数据需先写入临时文件。文件放在单独的临时目录中，
+/// 以保留宿主提供的文件名（服务器据此识别格式）。目录名随机生成，仅当前用户可访问，
+/// 其中的文件以独占方式新建。
+struct TempFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(filename_hint: Option<&str>, mime: Option<&str>) -> Result<(Self, File), Error> {
+        let mut filename = filename_hint
+            .and_then(|hint| Path::new(hint).file_name())
+            .and_then(OsStr::to_str)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(DEFAULT_FILENAME)
+            .to_string();
+        if Path::new(&filename).extension().is_none() {
+            if let Some(ext) = mime.and_then(extension_for_mime) {
+                filename = format!("{}.{}", filename, ext);
+            }
+        }
+
+        let temp_dir = std::env::temp_dir();
+        let mut attempt = 0;
+        let dir = loop {
+            let dir = temp_dir.join(random_temp_name());
+            match create_private_dir(&dir) {
+                Ok(()) => break dir,
+                Err(e)
+                    if e.kind() == ErrorKind::AlreadyExists && attempt + 1 < TEMP_DIR_ATTEMPTS =>
+                {
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(Error::OtherError(format!(
+                        "Failed to create temp dir {}: {}",
+                        dir.display(),
+                        e
+                    )))
+                }
+            }
+        };
+
+        let temp = Self {
+            path: dir.join(filename),
+            dir,
+        };
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = options.open(&temp.path).map_err(|e| {
+            Error::OtherError(format!(
+                "Failed to create temp file {}: {}",
+                temp.path.display(),
+                e
+            ))
+        })?;
+        Ok((temp, file))
+    }
+
+    fn path(&self) -> Result<&str, Error> {
+        self.path
+            .to_str()
+            .ok_or_else(|| Error::OtherError("Temp file path is not valid UTF-8".to_string()))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// 常见音频 MIME 类型对应的文件扩展名
+fn extension_for_mime(mime: &str) -> Option<&'static str> {
+    let mime = mime.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/wav" | "audio/wave" | "audio/x-wav" => "wav",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/ogg" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/webm" => "webm",
+        "audio/amr" => "amr",
+        "video/mp4" => "mp4",
+        _ => return None,
+    })
+}
+
+fn write_error(e: std::io::Error) -> Error {
+    Error::OtherError(format!("Failed to write temp file: {}", e))
+}
+
+/// 上传文件，token 失效时刷新后重试一次
+pub(crate) async fn upload_file(
+    filepath: &str,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: &str,
+    token: &str,
+) -> Result<UploadResponse, Error> {
+    with_token_refresh(token, |token| async move {
+        let model = model_type_from_str(model)?;
+        track_upload(
+            filepath,
+            upload(filepath, transcribe_only, short_asr, model, &token),
+        )
+        .await
+    })
+    .await
+}
+
+/// 上传文件（同步）
+fn upload_path(
+    filepath: &str,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: &str,
+    token: &str,
+) -> Result<UploadResponse, FfiError> {
+    block_on(upload_file(
+        filepath,
+        transcribe_only,
+        short_asr,
+        model,
+        token,
+    ))
+}
+
+fn parse_optional_str(s: *const c_char) -> Result<Option<String>, Error> {
+    if s.is_null() {
+        Ok(None)
+    } else {
+        parse_c_str(s, |s| Ok(Some(s.to_string())))
+    }
+}
+
+/// 上传内存中的音频数据进行转写
+///
+/// SDK 只支持从文件上传，数据会先写入临时文件，上传结束后删除。
+///
+/// # 参数
+/// - `data`: 音频数据
+/// - `len`: 数据长度
+/// - `filename_hint`: 文件名（C 字符串，可为 null），服务器据扩展名识别格式
+/// - `mime`: MIME 类型（C 字符串，可为 null），文件名没有扩展名时用于推断扩展名
+/// - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+/// - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `token`: Bearer token（C 字符串）
+/// - `out_result`: 输出上传结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_bytes(
+    data: *const u8,
+    len: usize,
+    filename_hint: *const c_char,
+    mime: *const c_char,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: *const c_char,
+    token: *const c_char,
+    out_result: *mut FfiUploadResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if data.is_null() || len == 0 || out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid input parameters".to_string()).into());
+        }
+        let filename_hint = parse_optional_str(filename_hint)?;
+        let mime = parse_optional_str(mime)?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        let (temp, mut file) = TempFile::create(filename_hint.as_deref(), mime.as_deref())?;
+        file.write_all(data).map_err(write_error)?;
+        drop(file);
+
+        let result = upload_path(temp.path()?, transcribe_only, short_asr, &model, &token)?;
+        let ffi_result = FfiUploadResponse::try_from(result)?;
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 通过读取回调上传音频数据进行转写
+///
+/// 适用于无法一次载入内存的大文件。数据在当前线程上分块读取并写入临时文件，
+/// 读取结束后上传，上传结束后删除临时文件。
+///
+/// # 参数
+/// - `read_cb`: 读取回调
+/// - `user_data`: 原样传给回调的用户数据
+/// - `filename_hint`: 文件名（C 字符串，可为 null），服务器据扩展名识别格式
+/// - `mime`: MIME 类型（C 字符串，可为 null），文件名没有扩展名时用于推断扩展名
+/// - `transcribe_only`: 是否仅转写（1 = true, 0 = false）
+/// - `short_asr`: 是否使用一句话转写模式（1 = true, 0 = false）
+/// - `model`: 模型类型字符串（"speed", "quality", "quality_v2"）
+/// - `token`: Bearer token（C 字符串）
+/// - `out_result`: 输出上传结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_reader(
+    read_cb: FfiReadCallback,
+    user_data: *mut c_void,
+    filename_hint: *const c_char,
+    mime: *const c_char,
+    transcribe_only: bool,
+    short_asr: bool,
+    model: *const c_char,
+    token: *const c_char,
+    out_result: *mut FfiUploadResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let read_cb = read_cb.ok_or_else(|| Error::InvalidInput("read_cb is null".to_string()))?;
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let filename_hint = parse_optional_str(filename_hint)?;
+        let mime = parse_optional_str(mime)?;
+        let model = parse_c_str(model, |s| Ok(s.to_string()))?;
+        model_type_from_str(&model)?;
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let (temp, mut file) = TempFile::create(filename_hint.as_deref(), mime.as_deref())?;
+        let mut buf = vec![0u8; READ_CHUNK_SIZE];
+        let mut total = 0usize;
+        loop {
+            let n = read_cb(buf.as_mut_ptr(), buf.len(), user_data);
+            if n < 0 {
+                return Err(Error::OtherError(format!(
+                    "Upload aborted by read callback with code {}",
+                    n
+                ))
+                .into());
+            }
+            if n == 0 {
+                break;
+            }
+            let n = n as usize;
+            if n > buf.len() {
+                return Err(Error::InvalidInput(format!(
+                    "read_cb returned {} bytes, more than the buffer size {}",
+                    n,
+                    buf.len()
+                ))
+                .into());
+            }
+            file.write_all(&buf[..n]).map_err(write_error)?;
+            total += n;
+        }
+        drop(file);
+        if total == 0 {
+            return Err(Error::InvalidInput("No data to upload".to_string()).into());
+        }
+
+        let result = upload_path(temp.path()?, transcribe_only, short_asr, &model, &token)?;
+        let ffi_result = FfiUploadResponse::try_from(result)?;
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 初始化上传选项
+///
+/// 将所有字段设为默认值，并将 `struct_size` 设为当前版本的结构体大小
+///
+/// # 参数
+/// - `options`: 上传选项结构体指针
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_options_init(options: *mut FfiUploadOptions) {
+    if options.is_null() {
+        return;
+    }
+
+    unsafe {
+        *options = FfiUploadOptions {
+            struct_size: std::mem::size_of::<FfiUploadOptions>(),
+            model: std::ptr::null(),
+            transcribe_only: false,
+            short_asr: false,
+        };
+    }
+}
+
+/// 按上传选项上传音频文件进行转写
+///
+/// # 参数
+/// - `filepath`: 音频文件路径（C 字符串）
+/// - `options`: 上传选项
+/// - `token`: Bearer token（C 字符串）
+/// - `out_result`: 输出上传结果结构体指针
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_upload_with_options(
+    filepath: *const c_char,
+    options: *const FfiUploadOptions,
+    token: *const c_char,
+    out_result: *mut FfiUploadResponse,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_result.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let filepath = parse_c_str(filepath, |s| Ok(s.to_string()))?;
+        let options = unsafe { UploadOptions::read(options)? };
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+
+        let result = upload_path(
+            &filepath,
+            options.transcribe_only,
+            options.short_asr,
+            &options.model,
+            &token,
+        )?;
+        let ffi_result = FfiUploadResponse::try_from(result)?;
+        unsafe {
+            *out_result = ffi_result;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
@@ -1,6 +1,7 @@
-use crate::error::{ErrorCode, FfiError};
+use crate::error::FfiError;
+use crate::panic;
 use common::Error;
-use std::ffi::{c_char, c_int, CStr, CString};
+use std::ffi::{c_char, c_int, CStr};
 use transcribe::transcribe::{ExportFormat, ExportType};
 use transcribe::types::{Language, ModelType};
 
@@ -21,13 +22,13 @@ pub fn parse_c_str<T, F: FnOnce(&str) -> Result<T, Error>>(
     }
 }
 
-pub fn parse_model_type(s: *const c_char) -> Result<ModelType, Error> {
-    parse_c_str(s, |s| match s.to_ascii_lowercase().as_str() {
+pub fn model_type_from_str(s: &str) -> Result<ModelType, Error> {
+    match s.to_ascii_lowercase().as_str() {
         "speed" => Ok(ModelType::Speed),
         "quality" => Ok(ModelType::Quality),
         "quality_v2" => Ok(ModelType::QualityV2),
         _ => Err(Error::InvalidInput("Invalid model type".to_string())),
-    })
+    }
 }
 
 pub fn parse_language(s: *const c_char) -> Result<Language, Error> {
@@ -42,43 +43,69 @@ pub fn parse_language(s: *const c_char) -> Result<Language, Error> {
     })
 }
 
-pub fn parse_format_type(s: *const c_char) -> Result<ExportFormat, Error> {
-    parse_c_str(s, |s| match s.to_ascii_lowercase().as_str() {
+pub fn format_type_from_str(s: &str) -> Result<ExportFormat, Error> {
+    match s.to_ascii_lowercase().as_str() {
         "pdf" => Ok(ExportFormat::Pdf),
         "txt" => Ok(ExportFormat::Txt),
         "docx" => Ok(ExportFormat::Docx),
         _ => Err(Error::InvalidInput("Invalid format type".to_string())),
-    })
+    }
 }
 
-pub fn parse_transcribe_export_type(s: *const c_char) -> Result<ExportType, Error> {
-    parse_c_str(s, |s| match s.to_ascii_lowercase().as_str() {
+pub fn transcribe_export_type_from_str(s: &str) -> Result<ExportType, Error> {
+    match s.to_ascii_lowercase().as_str() {
         "transcript" => Ok(ExportType::Transcript),
         "overview" => Ok(ExportType::Overview),
         "summary" => Ok(ExportType::Summary),
         _ => Err(Error::InvalidInput("Invalid export type".to_string())),
-    })
+    }
+}
+
+/// 读取以 `struct_size` 为首字段的可扩展选项结构体
+///
+/// 只复制 `struct_size` 覆盖的字节，未覆盖的字段为 0（即默认值）。全程通过裸指针读取，
+/// 使用旧版本头文件编译的调用方传入的结构体可以比 `T` 小。`ptr` 为 null 时返回 None，
+/// `struct_size` 小于 `min_size` 时返回错误。
+///
+/// # Safety
+/// `ptr` 为 null 或指向至少 `struct_size` 字节的可读内存；`T` 为 `#[repr(C)]` 结构体，
+/// 首字段为 `usize`，且各字段全 0 时为合法值
+pub unsafe fn read_sized_struct<T>(ptr: *const T, min_size: usize) -> Result<Option<T>, Error> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let size = std::ptr::read_unaligned(ptr as *const usize);
+    if size < min_size.max(std::mem::size_of::<usize>()) {
+        return Err(Error::InvalidInput(format!(
+            "Invalid options struct_size: {}",
+            size
+        )));
+    }
+
+    let mut copy = std::mem::MaybeUninit::<T>::zeroed();
+    std::ptr::copy_nonoverlapping(
+        ptr as *const u8,
+        copy.as_mut_ptr() as *mut u8,
+        size.min(std::mem::size_of::<T>()),
+    );
+    Ok(Some(copy.assume_init()))
 }
 
-pub fn ffi_execute<F>(error: *mut FfiError, f: F) -> c_int
+pub fn ffi_execute<F, E>(error: *mut FfiError, f: F) -> c_int
 where
-    F: FnOnce() -> Result<(), Error> + std::panic::UnwindSafe,
+    F: FnOnce() -> Result<(), E> + std::panic::UnwindSafe,
+    E: Into<FfiError>,
 {
+    panic::install_hook();
+    panic::clear_panic();
+
     let result = std::panic::catch_unwind(f);
     match result {
         Ok(Ok(())) => 0,
         Ok(Err(e)) => FfiError::fill_error(error, e),
-        Err(_) => {
-            let code = ErrorCode::UnknownError;
-            if !error.is_null() {
-                unsafe {
-                    (*error).code = code; // Generic error
-                    let msg = CString::new("Rust panic occurred").unwrap();
-                    (*error).message = msg.into_raw();
-                }
-            }
-
-            code as c_int
+        Err(payload) => {
+            let message = panic::take_panic_message(payload.as_ref());
+            FfiError::fill_error(error, FfiError::panic(message))
         }
     }
 }
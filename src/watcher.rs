@@ -0,0 +1,648 @@
+use std::collections::{HashMap, VecDeque};
+use std::ffi::*;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use common::Error;
+use tokio::sync::Notify;
+use tokio::task::{AbortHandle, JoinSet};
+use transcribe::transcribe::TranscribeStatus;
+
+use crate::{
+    error::{error_code, is_retryable, FfiError},
+    operation::UserData,
+    poll::{fetch_status, FfiPollConfig, PollConfig},
+    runtime::get_runtime,
+    transcribe_ffi_free_error, transcribe_ffi_free_transcribe_status,
+    types_ffi::free_c_string,
+    utils::*,
+    FfiTranscribeStatus,
+};
+
+/// 默认的每秒最大状态请求数
+const DEFAULT_MAX_REQUESTS_PER_SEC: u32 = 10;
+
+/// 事件队列的容量，队列满时暂停轮询，直到宿主取出事件
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+/// 任务事件类型
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FfiWatchEventKind {
+    /// 任务状态变化（尚未结束）
+    StatusChanged,
+    /// 任务已完成，之后不再监视
+    Completed,
+    /// 任务失败（失败状态、不可重试的查询错误、状态无法转换或超时），之后不再监视
+    Failed,
+}
+
+/// 任务事件
+#[repr(C)]
+pub struct FfiWatchEvent {
+    /// 事件类型
+    pub kind: FfiWatchEventKind,
+    /// 任务ID（C 字符串）
+    pub task_id: *mut c_char,
+    /// 任务状态，查询出错时为 null
+    pub status: *mut FfiTranscribeStatus,
+    /// 查询错误，有状态时为 null
+    pub error: *mut FfiError,
+}
+
+/// 任务事件回调
+///
+/// 事件只在回调期间有效，回调返回后由本库释放。回调在 runtime 工作线程上调用，
+/// 不能在回调中调用 `transcribe_ffi_watcher_free` 或同步请求接口（后者返回 `InvalidInput` 错误）；
+/// `transcribe_ffi_watcher_free` 返回后不会再调用回调。
+pub type FfiWatchCallback =
+    Option<extern "C" fn(event: *const FfiWatchEvent, user_data: *mut c_void)>;
+
+/// 事件携带的结果
+enum WatchOutcome {
+    Status(TranscribeStatus),
+    Error(Error),
+    Timeout(Duration),
+}
+
+/// 已转换为 C 结构体的事件，未交给宿主就被丢弃时释放
+struct WatchEvent(FfiWatchEvent);
+
+// 事件中的指针由 WatchEvent 独占，可以在线程间转移
+unsafe impl Send for WatchEvent {}
+
+impl WatchEvent {
+    /// 转换事件，状态无法转换时事件变为 `Failed`
+    fn new(kind: FfiWatchEventKind, task_id: &str, outcome: WatchOutcome) -> Self {
+        let status = match outcome {
+            WatchOutcome::Status(status) => {
+                FfiTranscribeStatus::try_from(status).map_err(FfiError::from)
+            }
+            WatchOutcome::Error(e) => Err(e.into()),
+            WatchOutcome::Timeout(after) => Err(FfiError::timeout(after)),
+        };
+        let (kind, status, error) = match status {
+            Ok(status) => (kind, Box::into_raw(Box::new(status)), std::ptr::null_mut()),
+            Err(e) => (
+                FfiWatchEventKind::Failed,
+                std::ptr::null_mut(),
+                Box::into_raw(Box::new(e)),
+            ),
+        };
+
+        Self(FfiWatchEvent {
+            kind,
+            task_id: CString::new(task_id)
+                .map(CString::into_raw)
+                .unwrap_or(std::ptr::null_mut()),
+            status,
+            error,
+        })
+    }
+
+    /// 任务是否已结束（完成或失败）
+    fn is_final(&self) -> bool {
+        self.0.kind != FfiWatchEventKind::StatusChanged
+    }
+
+    /// 交出 C 结构体，之后由调用方释放
+    fn into_ffi(self) -> FfiWatchEvent {
+        let event = std::mem::ManuallyDrop::new(self);
+        unsafe { std::ptr::read(&event.0) }
+    }
+}
+
+impl Drop for WatchEvent {
+    fn drop(&mut self) {
+        transcribe_ffi_free_watch_event(&mut self.0);
+    }
+}
+
+/// 单个任务的轮询状态
+struct WatchEntry {
+    added: Instant,
+    next_poll: Instant,
+    interval: Duration,
+    last_status: Option<String>,
+    /// 是否有正在进行的查询
+    polling: bool,
+}
+
+struct WatcherInner {
+    tasks: HashMap<String, WatchEntry>,
+    events: VecDeque<WatchEvent>,
+    /// 监视器已关闭（句柄释放或 runtime 关闭），不再接受新任务
+    closed: bool,
+    /// 后台任务已结束，不会再调用回调
+    finished: bool,
+}
+
+struct WatcherShared {
+    inner: Mutex<WatcherInner>,
+    /// 首次轮询后的轮询间隔
+    initial_interval: Duration,
+    /// 是否通过事件队列（而不是回调）投递事件
+    queued: bool,
+    /// 事件队列非空、监视器关闭或后台任务结束时通知
+    event_ready: Condvar,
+    /// 任务列表变化时唤醒后台任务
+    wake: Notify,
+}
+
+/// 下一步要做的事
+enum Next {
+    /// 查询该任务的状态
+    Poll(String),
+    /// 等待到指定时间（None 表示等待任务列表变化）
+    Wait(Option<Instant>),
+}
+
+impl WatcherShared {
+    fn lock(&self) -> MutexGuard<'_, WatcherInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.event_ready.notify_all();
+    }
+
+    /// 标记后台任务已结束
+    fn finish(&self) {
+        let mut inner = self.lock();
+        inner.closed = true;
+        inner.finished = true;
+        drop(inner);
+        self.event_ready.notify_all();
+    }
+
+    /// 等待后台任务结束
+    fn wait_finished(&self) {
+        let guard = self.lock();
+        let _guard = self
+            .event_ready
+            .wait_while(guard, |inner| !inner.finished)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+
+    /// 取出最早到期的任务；`slot` 为限流允许的下一次请求时间
+    fn next(&self, now: Instant, slot: Instant) -> Next {
+        let mut inner = self.lock();
+        // 事件队列已满时暂停轮询，由 `next_event` 唤醒
+        if self.queued && inner.events.len() >= MAX_QUEUED_EVENTS {
+            return Next::Wait(None);
+        }
+        let Some((task_id, entry)) = inner
+            .tasks
+            .iter_mut()
+            .filter(|(_, entry)| !entry.polling)
+            .min_by_key(|(_, entry)| entry.next_poll)
+        else {
+            return Next::Wait(None);
+        };
+
+        let due = entry.next_poll.max(slot);
+        if due > now {
+            return Next::Wait(Some(due));
+        }
+        entry.polling = true;
+        Next::Poll(task_id.clone())
+    }
+
+    /// 处理一次查询结果，返回产生的事件
+    fn update(
+        &self,
+        config: &PollConfig,
+        task_id: String,
+        result: Result<TranscribeStatus, Error>,
+    ) -> Vec<WatchEvent> {
+        let mut inner = self.lock();
+        // 查询期间任务已被移除
+        let Some(entry) = inner.tasks.get_mut(&task_id) else {
+            return Vec::new();
+        };
+        entry.polling = false;
+
+        let mut events = Vec::new();
+        let mut done = false;
+        match result {
+            Ok(status) if config.is_terminal(&status.status) => {
+                let kind = if config.is_failure(&status.status) {
+                    FfiWatchEventKind::Failed
+                } else {
+                    FfiWatchEventKind::Completed
+                };
+                log::debug!("Task {} finished with status {}", task_id, status.status);
+                events.push((kind, WatchOutcome::Status(status)));
+                done = true;
+            }
+            Ok(status) if entry.last_status.as_deref() != Some(status.status.as_str()) => {
+                log::debug!("Task {} status: {}", task_id, status.status);
+                entry.last_status = Some(status.status.clone());
+                entry.interval = config.initial_interval;
+                events.push((
+                    FfiWatchEventKind::StatusChanged,
+                    WatchOutcome::Status(status),
+                ));
+            }
+            Ok(_) => entry.interval = config.next_interval(entry.interval),
+            Err(e) if is_retryable(&e) => {
+                log::warn!(
+                    "Failed to poll task {} with {:?}, will retry",
+                    task_id,
+                    error_code(&e)
+                );
+                entry.interval = config.next_interval(entry.interval);
+            }
+            Err(e) => {
+                events.push((FfiWatchEventKind::Failed, WatchOutcome::Error(e)));
+                done = true;
+            }
+        }
+
+        let mut events: Vec<WatchEvent> = events
+            .into_iter()
+            .map(|(kind, outcome)| WatchEvent::new(kind, &task_id, outcome))
+            .collect();
+        // 状态无法转换时事件变为 Failed，同样结束监视
+        done |= events.iter().any(WatchEvent::is_final);
+
+        let now = Instant::now();
+        if let Some(timeout) = config.timeout {
+            if !done && now.duration_since(entry.added) >= timeout {
+                log::warn!("Task {} timed out", task_id);
+                events.push(WatchEvent::new(
+                    FfiWatchEventKind::Failed,
+                    &task_id,
+                    WatchOutcome::Timeout(timeout),
+                ));
+                done = true;
+            }
+        }
+        if done {
+            inner.tasks.remove(&task_id);
+        } else {
+            entry.next_poll = now + entry.interval;
+        }
+
+        events
+    }
+
+    /// 等待并取出下一个事件，超时返回 None
+    fn next_event(&self, timeout: Duration) -> Result<Option<WatchEvent>, FfiError> {
+        let guard = self.lock();
+        let mut guard = self
+            .event_ready
+            .wait_timeout_while(guard, timeout, |inner| {
+                inner.events.is_empty() && !inner.closed
+            })
+            .unwrap_or_else(PoisonError::into_inner)
+            .0;
+        let was_full = guard.events.len() >= MAX_QUEUED_EVENTS;
+        match guard.events.pop_front() {
+            Some(event) => {
+                if was_full {
+                    self.wake.notify_one();
+                }
+                Ok(Some(event))
+            }
+            None if guard.closed => Err(FfiError::cancelled()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// 后台轮询所有任务
+///
+/// 所有任务共享同一个限流器，每次请求之间至少间隔 `rate_period`
+async fn drive(
+    shared: Arc<WatcherShared>,
+    token: String,
+    config: PollConfig,
+    rate_period: Duration,
+    callback: FfiWatchCallback,
+    user_data: UserData,
+) {
+    let mut polls = JoinSet::new();
+    let mut slot = Instant::now();
+    loop {
+        let now = Instant::now();
+        let wait = match shared.next(now, slot) {
+            Next::Poll(task_id) => {
+                slot = now + rate_period;
+                let token = token.clone();
+                polls.spawn(async move {
+                    let result = fetch_status(&task_id, &token).await;
+                    (task_id, result)
+                });
+                continue;
+            }
+            Next::Wait(wait) => wait,
+        };
+
+        tokio::select! {
+            Some(joined) = polls.join_next() => {
+                let Ok((task_id, result)) = joined else {
+                    continue;
+                };
+                let events = shared.update(&config, task_id, result);
+                match callback {
+                    Some(callback) => {
+                        for event in events {
+                            let mut event = event.into_ffi();
+                            callback(&event, user_data.get());
+                            transcribe_ffi_free_watch_event(&mut event);
+                        }
+                    }
+                    None if !events.is_empty() => {
+                        shared.lock().events.extend(events);
+                        shared.event_ready.notify_all();
+                    }
+                    None => {}
+                }
+            }
+            _ = shared.wake.notified() => {}
+            _ = async {
+                match wait {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
+                }
+            } => {}
+        }
+    }
+}
+
+/// 任务监视器句柄
+/// 注意：这是一个不透明的指针类型，C 代码不应该直接访问其内部字段
+pub struct TaskWatcher {
+    shared: Arc<WatcherShared>,
+    abort: AbortHandle,
+}
+
+impl TaskWatcher {
+    unsafe fn from_ptr<'a>(watcher: *const TaskWatcher) -> Result<&'a Self, Error> {
+        watcher
+            .as_ref()
+            .ok_or_else(|| Error::InvalidInput("Invalid watcher handle".to_string()))
+    }
+}
+
+/// 创建任务监视器
+///
+/// 监视器在后台轮询已添加的任务：状态未变化时按 `poll_config` 逐步拉长间隔，
+/// 所有任务共享每秒最大请求数的限制。状态变化、完成和失败时产生事件；
+/// 提供回调时在 runtime 工作线程上调用回调，否则放入事件队列，
+/// 由 `transcribe_ffi_watcher_next_event` 取出。队列中积压约 1024 个事件时暂停轮询，
+/// 直到宿主取出事件，因此不取出事件不会无限占用内存。
+/// 监视器不计入 `transcribe_ffi_runtime_shutdown` 等待的操作，runtime 关闭后停止工作。
+///
+/// # 参数
+/// - `token`: Bearer token（C 字符串）
+/// - `poll_config`: 轮询配置，为 null 时使用默认配置；`timeout_ms` 为每个任务从添加起的超时时间
+/// - `max_requests_per_sec`: 每秒最大状态请求数，0 表示使用默认值 10
+/// - `callback`: 事件回调，为 null 时使用事件队列
+/// - `user_data`: 原样传给回调的用户数据
+/// - `out_watcher`: 输出监视器句柄指针，需调用 `transcribe_ffi_watcher_free` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_create(
+    token: *const c_char,
+    poll_config: *const FfiPollConfig,
+    max_requests_per_sec: u32,
+    callback: FfiWatchCallback,
+    user_data: *mut c_void,
+    out_watcher: *mut *mut TaskWatcher,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_watcher.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let token = parse_c_str(token, |s| Ok(s.to_string()))?;
+        let config = PollConfig::from_ptr(poll_config)?;
+        let max_requests_per_sec = match max_requests_per_sec {
+            0 => DEFAULT_MAX_REQUESTS_PER_SEC,
+            n => n,
+        };
+        let rate_period = Duration::from_secs(1) / max_requests_per_sec;
+
+        let shared = Arc::new(WatcherShared {
+            inner: Mutex::new(WatcherInner {
+                tasks: HashMap::new(),
+                events: VecDeque::new(),
+                closed: false,
+                finished: false,
+            }),
+            initial_interval: config.initial_interval,
+            queued: callback.is_none(),
+            event_ready: Condvar::new(),
+            wake: Notify::new(),
+        });
+
+        // 后台任务被丢弃（句柄释放或 runtime 关闭）时唤醒等待事件和等待释放的线程
+        struct FinishOnDrop(Arc<WatcherShared>);
+
+        impl Drop for FinishOnDrop {
+            fn drop(&mut self) {
+                self.0.finish();
+            }
+        }
+
+        let runtime = get_runtime()?;
+        let guard = FinishOnDrop(shared.clone());
+        let user_data = UserData::new(user_data);
+        // 监视器常驻运行，不通过 runtime::spawn 计入关闭时等待的操作
+        let task = runtime.spawn(async move {
+            let shared = guard.0.clone();
+            let _guard = guard;
+            drive(shared, token, config, rate_period, callback, user_data).await
+        });
+
+        let watcher = Box::new(TaskWatcher {
+            shared,
+            abort: task.abort_handle(),
+        });
+        unsafe {
+            *out_watcher = Box::into_raw(watcher);
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+/// 添加要监视的任务
+///
+/// 任务在下一个限流时隙被首次查询。已在监视中的任务不会重复添加。
+///
+/// # 参数
+/// - `watcher`: 监视器句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_add(
+    watcher: *const TaskWatcher,
+    task_id: *const c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let watcher = unsafe { TaskWatcher::from_ptr(watcher)? };
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+
+        let mut inner = watcher.shared.lock();
+        if inner.closed {
+            return Err(Error::InvalidInput("Watcher is closed".to_string()));
+        }
+        let now = Instant::now();
+        inner.tasks.entry(task_id).or_insert_with(|| WatchEntry {
+            added: now,
+            next_poll: now,
+            interval: watcher.shared.initial_interval,
+            last_status: None,
+            polling: false,
+        });
+        drop(inner);
+        watcher.shared.wake.notify_one();
+
+        Ok(())
+    })
+}
+
+/// 停止监视任务
+///
+/// 正在进行的查询结果会被丢弃，不再产生该任务的新事件（已在队列中的事件仍可取出）。
+/// 任务不在监视中时无效果。
+///
+/// # 参数
+/// - `watcher`: 监视器句柄
+/// - `task_id`: 任务ID（C 字符串）
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_remove(
+    watcher: *const TaskWatcher,
+    task_id: *const c_char,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        let watcher = unsafe { TaskWatcher::from_ptr(watcher)? };
+        let task_id = parse_c_str(task_id, |s| Ok(s.to_string()))?;
+
+        watcher.shared.lock().tasks.remove(&task_id);
+        watcher.shared.wake.notify_one();
+
+        Ok::<(), Error>(())
+    })
+}
+
+/// 获取正在监视的任务数
+///
+/// # 参数
+/// - `watcher`: 监视器句柄
+///
+/// # 返回
+/// 任务数；句柄为 null 时返回 0
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_count(watcher: *const TaskWatcher) -> usize {
+    match unsafe { watcher.as_ref() } {
+        Some(watcher) => watcher.shared.lock().tasks.len(),
+        None => 0,
+    }
+}
+
+/// 从事件队列取出下一个事件
+///
+/// 仅适用于创建时未提供回调的监视器。
+///
+/// # 参数
+/// - `watcher`: 监视器句柄
+/// - `timeout_ms`: 队列为空时的等待时间（毫秒），0 表示立即返回
+/// - `out_event`: 输出事件结构体指针，需调用 `transcribe_ffi_free_watch_event` 释放
+/// - `out_error`: 错误信息输出指针，如果为 null 则不填充错误信息
+/// # 返回
+/// 错误码（0 表示成功，超时时 `task_id` 为 null；runtime 已关闭时为 `Cancelled`）
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_next_event(
+    watcher: *const TaskWatcher,
+    timeout_ms: u64,
+    out_event: *mut FfiWatchEvent,
+    out_error: *mut FfiError,
+) -> c_int {
+    ffi_execute(out_error, || {
+        if out_event.is_null() {
+            return Err(Error::InvalidInput("Invalid output parameters".to_string()).into());
+        }
+        let watcher = unsafe { TaskWatcher::from_ptr(watcher)? };
+        if !watcher.shared.queued {
+            return Err(Error::InvalidInput(
+                "Watcher delivers events through its callback".to_string(),
+            )
+            .into());
+        }
+
+        let event = match watcher
+            .shared
+            .next_event(Duration::from_millis(timeout_ms))?
+        {
+            Some(event) => event.into_ffi(),
+            None => FfiWatchEvent {
+                kind: FfiWatchEventKind::StatusChanged,
+                task_id: std::ptr::null_mut(),
+                status: std::ptr::null_mut(),
+                error: std::ptr::null_mut(),
+            },
+        };
+        unsafe {
+            *out_event = event;
+        }
+
+        Ok::<(), FfiError>(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_free_watch_event(e: *mut FfiWatchEvent) {
+    if e.is_null() {
+        return;
+    }
+    unsafe {
+        free_c_string(&mut (*e).task_id);
+        if !(*e).status.is_null() {
+            let mut status = Box::from_raw((*e).status);
+            transcribe_ffi_free_transcribe_status(&mut *status);
+            (*e).status = std::ptr::null_mut();
+        }
+        if !(*e).error.is_null() {
+            let mut error = Box::from_raw((*e).error);
+            transcribe_ffi_free_error(&mut *error);
+            (*e).error = std::ptr::null_mut();
+        }
+    }
+}
+
+/// 释放监视器句柄，停止轮询并丢弃未取出的事件
+///
+/// 阻塞直到后台任务结束（包括等待正在执行的回调返回），返回后不会再调用回调。
+/// 不能在回调中调用；在 runtime 线程上调用时不等待并记录警告。
+///
+/// # 参数
+/// - `watcher`: 监视器句柄
+#[no_mangle]
+pub extern "C" fn transcribe_ffi_watcher_free(watcher: *mut TaskWatcher) {
+    if watcher.is_null() {
+        return;
+    }
+
+    let watcher = unsafe { Box::from_raw(watcher) };
+    watcher.abort.abort();
+    watcher.shared.close();
+    if tokio::runtime::Handle::try_current().is_ok() {
+        log::warn!("Watcher freed on a runtime thread, not waiting for its callbacks");
+        return;
+    }
+    watcher.shared.wait_finished();
+}